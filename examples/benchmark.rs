@@ -0,0 +1,45 @@
+// Memetic minimization of a 10-dimensional Rastrigin function: gaussian
+// mutation plus Nelder-Mead on a fifth of the offspring.
+//
+//     cargo run --example benchmark
+extern crate genetic_thing;
+
+use genetic_thing::genetic::crossover::uniform_crossover;
+use genetic_thing::genetic::helpers::euclidean_distance_float;
+use genetic_thing::genetic::local_search::{NelderMead, WriteBack};
+use genetic_thing::genetic::mutation::gaussian_mutation;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::benchmarks::benchmark;
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let rastrigin = benchmark("rastrigin").unwrap().with_dimensions(10).unwrap();
+    let evaluated_rastrigin = rastrigin.clone();
+
+    let mut population = Population::<f32>::new(100,
+                                                rastrigin.dimensions,
+                                                0.9,
+                                                0.05,
+                                                rastrigin.bounds,
+                                                true,
+                                                true,
+                                                false,
+                                                false,
+                                                1,
+                                                euclidean_distance_float,
+                                                move |genome, _| evaluated_rastrigin.fitness(genome),
+                                                uniform_crossover,
+                                                gaussian_mutation);
+    population.set_local_search(NelderMead { step: 0.05 }, 0.2, 50, WriteBack::Lamarckian);
+    population.run(300);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    println!("{}({}) = {} (known minimum: {:?}) after {} evaluations",
+             rastrigin.name,
+             rastrigin.dimensions,
+             rastrigin.evaluate(&best_individual.genome),
+             rastrigin.minimum(),
+             population.evaluations);
+}
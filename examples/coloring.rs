@@ -0,0 +1,43 @@
+// Three-coloring the Petersen graph.
+//
+//     cargo run --example coloring
+extern crate genetic_thing;
+
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::uniform_crossover;
+use genetic_thing::genetic::helpers::euclidean_distance_int;
+use genetic_thing::genetic::mutation::random_int;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::coloring::GraphColoring;
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let graph = Rc::new(GraphColoring::load("data/coloring/petersen.col", 3)
+        .expect("run the examples from the repository root"));
+    let evaluated_graph = graph.clone();
+
+    let mut population = Population::<i32>::new(100,
+                                                graph.vertices,
+                                                0.9,
+                                                1.0 / graph.vertices as f32,
+                                                graph.range(),
+                                                true,
+                                                true,
+                                                false,
+                                                false,
+                                                1,
+                                                euclidean_distance_int,
+                                                move |genome, _| evaluated_graph.fitness(genome),
+                                                uniform_crossover,
+                                                random_int);
+    population.run(200);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    println!("Colors used: {}, conflicting edges: {}, coloring: {:?}",
+             graph.colors_used(&best_individual.genome),
+             graph.conflicts(&best_individual.genome),
+             best_individual.genome);
+}
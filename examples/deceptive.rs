@@ -0,0 +1,40 @@
+// The original experiment: a 30-bit deceptive F3 with sharing, scaling,
+// elitism and a generation gap, plotted with gnuplot at the end.
+//
+//     cargo run --example deceptive
+extern crate genetic_thing;
+
+use genetic_thing::genetic::crossover::one_point_crossover;
+use genetic_thing::genetic::fitness::deceptive_f3s;
+use genetic_thing::genetic::helpers::{Range, hamming_distance};
+use genetic_thing::genetic::mutation::bit_flip;
+use genetic_thing::genetic::plot::show_plots;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::random;
+
+fn main() {
+    let total_generations = 10000;
+    random::seed(0);
+
+    let mut population = Population::<u8>::new(50,
+                                               30,
+                                               0.98,
+                                               0.001,
+                                               Range::new(0, 1),
+                                               true,
+                                               true,
+                                               true,
+                                               true,
+                                               20,
+                                               hamming_distance,
+                                               deceptive_f3s,
+                                               one_point_crossover,
+                                               bit_flip);
+    population.run(total_generations);
+
+    if let Some(diagnostic) = population.best_individual_diagnostic() {
+        println!("{}", diagnostic);
+    }
+    println!("Fitness evaluations: {}", population.evaluations);
+    show_plots(&population);
+}
@@ -0,0 +1,52 @@
+// 0/1 knapsack from data/knapsack, with infeasible genomes repaired or
+// penalized.
+//
+//     cargo run --example knapsack
+extern crate genetic_thing;
+
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::uniform_crossover;
+use genetic_thing::genetic::helpers::{Range, hamming_distance};
+use genetic_thing::genetic::mutation::bit_flip;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::knapsack::{Knapsack, ConstraintHandling};
+use genetic_thing::genetic::random;
+
+fn main() {
+    for &handling in &[ConstraintHandling::Repair, ConstraintHandling::Penalty] {
+        random::seed(0);
+        let knapsack = Rc::new(Knapsack::load("data/knapsack/p01.txt")
+            .expect("run the examples from the repository root")
+            .with_handling(handling));
+        let evaluated_knapsack = knapsack.clone();
+        let length = knapsack.values.len();
+
+        let mut population = Population::<u8>::new(100,
+                                                   length,
+                                                   0.9,
+                                                   1.0 / length as f32,
+                                                   Range::new(0, 1),
+                                                   true,
+                                                   true,
+                                                   false,
+                                                   false,
+                                                   1,
+                                                   hamming_distance,
+                                                   move |genome, _| evaluated_knapsack.fitness(genome),
+                                                   uniform_crossover,
+                                                   bit_flip);
+        population.run(200);
+
+        // Repair only happens inside the fitness, the genome itself may be overweight
+        let solution = knapsack.decode(&population.best_individual_in_generation.last().unwrap().genome);
+        println!("{:?}: value {} of {:?}, weight {} of {}, {}",
+                 handling,
+                 knapsack.value(&solution),
+                 knapsack.optimum(),
+                 knapsack.weight(&solution),
+                 knapsack.capacity,
+                 if knapsack.is_feasible(&solution) { "feasible" } else { "infeasible" });
+    }
+}
@@ -0,0 +1,45 @@
+// MAX-SAT on a planted 20-variable formula, with bit-flip hill climbing on a
+// tenth of the offspring.
+//
+//     cargo run --example maxsat
+extern crate genetic_thing;
+
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::uniform_crossover;
+use genetic_thing::genetic::helpers::{Range, hamming_distance};
+use genetic_thing::genetic::local_search::{BitFlipHillClimbing, WriteBack};
+use genetic_thing::genetic::mutation::bit_flip;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::sat::MaxSat;
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let formula = Rc::new(MaxSat::load("data/sat/planted20.cnf").expect("run the examples from the repository root"));
+    let evaluated_formula = formula.clone();
+
+    let mut population = Population::<u8>::new(100,
+                                               formula.variables,
+                                               0.9,
+                                               1.0 / formula.variables as f32,
+                                               Range::new(0, 1),
+                                               true,
+                                               true,
+                                               false,
+                                               false,
+                                               1,
+                                               hamming_distance,
+                                               move |genome, _| evaluated_formula.fitness(genome),
+                                               uniform_crossover,
+                                               bit_flip);
+    population.set_local_search(BitFlipHillClimbing, 0.1, 50, WriteBack::Lamarckian);
+    population.run(100);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    println!("{} of {} clauses satisfied after {} evaluations",
+             formula.satisfied_clauses(&best_individual.genome),
+             formula.clauses.len(),
+             population.evaluations);
+}
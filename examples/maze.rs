@@ -0,0 +1,38 @@
+// Walks the default maze with relative turn genes.
+//
+//     cargo run --example maze
+extern crate genetic_thing;
+
+use genetic_thing::genetic::crossover::one_point_crossover;
+use genetic_thing::genetic::helpers::{Range, euclidean_distance_int};
+use genetic_thing::genetic::mutation::random_int;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::{Problem, Render};
+use genetic_thing::genetic::problems::maze::{Maze, MoveEncoding};
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let maze = Maze::default_maze().with_encoding(MoveEncoding::RelativeTurn);
+    let evaluated_maze = maze.clone();
+
+    let mut population = Population::<i32>::new(100,
+                                                maze.width * maze.height / 2,
+                                                0.9,
+                                                0.01,
+                                                Range::new(0, maze.encoding.max_gene()),
+                                                true,
+                                                true,
+                                                false,
+                                                false,
+                                                1,
+                                                euclidean_distance_int,
+                                                move |genome, _| evaluated_maze.fitness(genome),
+                                                one_point_crossover,
+                                                random_int);
+    population.run(300);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    println!("Best fitness {} after {} evaluations", maze.fitness(&best_individual.genome), population.evaluations);
+    print!("{}", maze.render_ascii(&best_individual.genome));
+}
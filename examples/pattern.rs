@@ -0,0 +1,44 @@
+// Evolves the default bitmap pattern from random pixels, with 5% noise on
+// every evaluation.
+//
+//     cargo run --example pattern
+extern crate genetic_thing;
+
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::uniform_crossover;
+use genetic_thing::genetic::helpers::{Range, hamming_distance};
+use genetic_thing::genetic::mutation::bit_flip;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::{Problem, Render};
+use genetic_thing::genetic::problems::pattern::{Bitmap, PatternMatching};
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let matcher = Rc::new(PatternMatching::new(vec![Bitmap::default_pattern()]).with_noise(0.05));
+    let evaluated_matcher = matcher.clone();
+    let length = matcher.genome_size();
+
+    let mut population = Population::<u8>::new(100,
+                                               length,
+                                               0.9,
+                                               1.0 / length as f32,
+                                               Range::new(0, 1),
+                                               true,
+                                               true,
+                                               false,
+                                               false,
+                                               1,
+                                               hamming_distance,
+                                               move |genome, _| evaluated_matcher.fitness(genome),
+                                               uniform_crossover,
+                                               bit_flip);
+    population.run(300);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    println!("Similarity {} after {} evaluations",
+             matcher.similarity(&best_individual.genome, &matcher.targets[0]),
+             population.evaluations);
+    print!("{}", matcher.render_ascii(&best_individual.genome));
+}
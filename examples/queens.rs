@@ -0,0 +1,44 @@
+// N-queens on permutations with PMX and swap mutation, drawing the best board.
+//
+//     cargo run --example queens
+extern crate genetic_thing;
+
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::partially_matched_crossover;
+use genetic_thing::genetic::helpers::euclidean_distance_int;
+use genetic_thing::genetic::mutation::swap_position;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::{Problem, Render};
+use genetic_thing::genetic::problems::queens::NQueens;
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let board = Rc::new(NQueens::new(16));
+    let evaluated_board = board.clone();
+
+    let mut population = Population::<i32>::new_ordered(100,
+                                                        16,
+                                                        0.9,
+                                                        0.05,
+                                                        board.range(),
+                                                        true,
+                                                        true,
+                                                        false,
+                                                        false,
+                                                        1,
+                                                        euclidean_distance_int,
+                                                        move |genome, _| evaluated_board.fitness(genome),
+                                                        partially_matched_crossover,
+                                                        swap_position);
+    population.run(500);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    let fitness = board.fitness(&best_individual.genome);
+    println!("Best fitness {} ({}) after {} evaluations",
+             fitness,
+             if board.is_solved(fitness) { "solved" } else { "not solved" },
+             population.evaluations);
+    print!("{}", board.render_ascii(&best_individual.genome));
+}
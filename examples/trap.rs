@@ -0,0 +1,40 @@
+// Concatenated 4-bit traps, tightly and loosely linked, to show how one-point
+// crossover depends on where the blocks sit.
+//
+//     cargo run --example trap
+extern crate genetic_thing;
+
+use genetic_thing::genetic::crossover::one_point_crossover;
+use genetic_thing::genetic::helpers::{Range, hamming_distance};
+use genetic_thing::genetic::mutation::bit_flip;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::binary::{Trap, Linkage};
+use genetic_thing::genetic::random;
+
+fn main() {
+    for &linkage in &[Linkage::Tight, Linkage::Loose] {
+        random::seed(0);
+        let trap = Trap::new(64, 4, linkage).unwrap();
+        let optimum = trap.optimum();
+
+        let mut population = Population::<u8>::new(200,
+                                                   64,
+                                                   0.9,
+                                                   1.0 / 64.0,
+                                                   Range::new(0, 1),
+                                                   true,
+                                                   true,
+                                                   false,
+                                                   false,
+                                                   1,
+                                                   hamming_distance,
+                                                   move |genome, _| trap.fitness(genome),
+                                                   one_point_crossover,
+                                                   bit_flip);
+        population.run(300);
+
+        println!("{:?} linkage: best fitness {} of {:?} after {} evaluations",
+                 linkage, population.best_fitness(), optimum, population.evaluations);
+    }
+}
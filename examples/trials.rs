@@ -0,0 +1,51 @@
+// Repeated seeded trials on OneMax at two mutation rates, summarized and
+// compared with Mann-Whitney, Wilcoxon and A12.
+//
+//     cargo run --example trials
+extern crate genetic_thing;
+
+use genetic_thing::genetic::crossover::uniform_crossover;
+use genetic_thing::genetic::experiment::{Experiment, Summary};
+use genetic_thing::genetic::helpers::{Range, hamming_distance};
+use genetic_thing::genetic::mutation::bit_flip;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::binary::OneMax;
+use genetic_thing::genetic::statistics::comparison_report;
+
+const LENGTH: usize = 100;
+
+fn summary(mutation_probability: f32) -> Summary {
+    let mut experiment = Experiment::new(20, 100);
+    experiment.target = OneMax { length: LENGTH }.optimum();
+    experiment.run(move || {
+        let problem = OneMax { length: LENGTH };
+        Population::<u8>::new(50,
+                              LENGTH,
+                              0.9,
+                              mutation_probability,
+                              Range::new(0, 1),
+                              true,
+                              true,
+                              false,
+                              false,
+                              1,
+                              hamming_distance,
+                              move |genome, _| problem.fitness(genome),
+                              uniform_crossover,
+                              bit_flip)
+    })
+}
+
+fn main() {
+    let names = vec!["1/L".to_string(), "5/L".to_string()];
+    let summaries = vec![summary(1.0 / LENGTH as f32), summary(5.0 / LENGTH as f32)];
+    for (name, summary) in names.iter().zip(&summaries) {
+        println!("Mutation rate {}:\n{}", name, summary.report());
+    }
+
+    let fitnesses: Vec<Vec<f32>> = summaries.iter()
+        .map(|summary| summary.trials.iter().map(|trial| trial.best_fitness()).collect())
+        .collect();
+    print!("{}", comparison_report("Final best fitness", &names, &fitnesses, true, 0.05));
+}
@@ -0,0 +1,46 @@
+// TSPLIB burma14 on permutations with order crossover and 2-opt mutation,
+// measured against the known optimal tour.
+//
+//     cargo run --example tsp
+extern crate genetic_thing;
+
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::order_crossover;
+use genetic_thing::genetic::helpers::{Range, euclidean_distance_int};
+use genetic_thing::genetic::mutation::two_opt_mutation;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::tsp::Tsp;
+use genetic_thing::genetic::random;
+
+fn main() {
+    random::seed(0);
+    let mut tsp = Tsp::load("data/tsp/burma14.tsp").expect("burma14 instance");
+    tsp.load_optimal_tour("data/tsp/burma14.opt.tour").expect("burma14 optimal tour");
+    let tsp = Rc::new(tsp);
+    let evaluated_tsp = tsp.clone();
+
+    let mut population = Population::<i32>::new_ordered(100,
+                                                        tsp.dimension,
+                                                        0.9,
+                                                        0.05,
+                                                        Range::new(0, tsp.dimension as i32 - 1),
+                                                        true,
+                                                        true,
+                                                        false,
+                                                        false,
+                                                        1,
+                                                        euclidean_distance_int,
+                                                        move |genome, _| evaluated_tsp.fitness(genome),
+                                                        order_crossover,
+                                                        two_opt_mutation);
+    population.run(300);
+
+    let best_individual = population.best_individual_in_generation.last().unwrap();
+    println!("Best tour {:?}: length {}, {:.2}% above the optimum, after {} evaluations",
+             best_individual.genome,
+             tsp.tour_length(&best_individual.genome),
+             tsp.gap(&best_individual.genome).unwrap(),
+             population.evaluations);
+}
@@ -0,0 +1,222 @@
+use std::f32;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use genetic_thing::genetic::experiment::{Experiment, Summary};
+use genetic_thing::genetic::problems::Problem;
+use genetic_thing::genetic::problems::queens::NQueens;
+use genetic_thing::genetic::statistics::comparison_report;
+use genetic_thing::genetic::sweep::{self, Sweep, Outcome};
+
+use cli::options::{Options, parse_options, USAGE};
+use cli::problems::{build_deceptive, load_benchmark, build_benchmark, bit_string_problem, build_bit_strings,
+                    load_maze, build_maze, build_queens, load_tsp, build_tsp, load_coloring, build_coloring,
+                    load_pattern, build_pattern};
+use cli::run::configure;
+
+pub fn run_experiment(options: Options) {
+    let results = options.results.clone();
+    let summary = experiment_summary(options);
+
+    print!("{}", summary.report());
+    if let Some(ref directory) = results {
+        if let Err(error) = summary.write_to(Path::new(directory)) {
+            eprintln!("Could not write results to {}: {}", directory, error);
+            process::exit(1);
+        }
+    }
+}
+
+// Runs --trials seeded trials of the configured problem, each on its own
+// freshly built problem and population.
+fn experiment_summary(mut options: Options) -> Summary {
+    options.target = options.target.or(match options.problem.as_str() {
+        "benchmark" => load_benchmark(&options).optimum(),
+        "binary" | "knapsack" | "maxsat" => bit_string_problem(&options).0.optimum(),
+        "maze" => load_maze(&options).optimum(),
+        "queens" => NQueens::new(options.board_size).optimum(),
+        "tsp" => load_tsp(&options).optimum(),
+        "coloring" => load_coloring(&options).optimum(),
+        "pattern" => load_pattern(&options).optimum(),
+        _ => None,
+    });
+    let options = Arc::new(options);
+    let mut experiment = Experiment::new(options.trials.unwrap_or(1), options.total_generations);
+    experiment.seed = options.seed;
+    experiment.threads = options.threads;
+    experiment.target = options.target;
+
+    match options.problem.as_str() {
+        "deceptive" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_deceptive(&options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "benchmark" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_benchmark(&load_benchmark(&options), &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "binary" | "knapsack" | "maxsat" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let (problem, length) = bit_string_problem(&options);
+                let mut population = build_bit_strings(problem, length, &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "maze" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_maze(&load_maze(&options), &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "queens" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_queens(Rc::new(NQueens::new(options.board_size)), &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "tsp" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_tsp(Rc::new(load_tsp(&options)), &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "coloring" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_coloring(Rc::new(load_coloring(&options)), &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        "pattern" => {
+            let options = options.clone();
+            experiment.run(move || {
+                let mut population = build_pattern(Rc::new(load_pattern(&options)), &options);
+                configure(&mut population, &options);
+                population
+            })
+        },
+        other => {
+            eprintln!("unknown problem {}\n{}", other, USAGE);
+            process::exit(1);
+        }
+    }
+}
+
+// Runs an experiment for every setting the sweep picks and prints them ranked.
+pub fn run_sweep(options: Options) {
+    let mut sweep = Sweep::new(options.sweep.clone(), options.sweep_strategy);
+    sweep.seed = options.seed;
+    let names = sweep.names();
+
+    let settings = sweep.settings();
+    let mut outcomes = Vec::with_capacity(settings.len());
+    for (index, mut setting) in settings.into_iter().enumerate() {
+        let mut trial_options = options.clone();
+        trial_options.trials = Some(options.trials.unwrap_or(5));
+        // Integer parameters are rounded in the setting too, so the ranking
+        // shows the values that were actually run
+        for (name, value) in names.iter().zip(setting.iter_mut()) {
+            match *name {
+                "population-size" => {
+                    *value = value.round().max(2.0);
+                    trial_options.population_size = Some(*value as usize);
+                },
+                "crossover-probability" => trial_options.crossover_probability = Some(*value),
+                "mutation-probability" => trial_options.mutation_probability = Some(*value),
+                "crowding-factor" => {
+                    *value = value.round().max(1.0);
+                    trial_options.crowding_factor = Some(*value as usize);
+                },
+                _ => unreachable!(),
+            }
+        }
+
+        let outcome = Outcome { setting: setting, summary: experiment_summary(trial_options) };
+        println!("Setting {}: {:?}, median final fitness {}", index + 1, outcome.setting, outcome.median_fitness());
+        outcomes.push(outcome);
+    }
+
+    sweep::rank(&mut outcomes);
+    print!("{}", sweep::table(&names, &outcomes));
+    if let Some(ref directory) = options.results {
+        let written = fs::create_dir_all(directory)
+            .and_then(|_| File::create(Path::new(directory).join("sweep.csv")))
+            .and_then(|mut file| file.write_all(sweep::table_csv(&names, &outcomes).as_bytes()));
+        if let Err(error) = written {
+            eprintln!("Could not write results to {}: {}", directory, error);
+            process::exit(1);
+        }
+    }
+}
+
+// Runs an experiment per --compare configuration, the shared options with the
+// configuration's own options on top, and tests the differences between them.
+// Trial `i` of every configuration gets the same seed, which pairs them.
+pub fn run_comparison(arguments: &[String], options: Options) {
+    let mut names = Vec::new();
+    let mut summaries = Vec::new();
+    for configuration in &options.compare {
+        let mut configuration_arguments = arguments.to_vec();
+        configuration_arguments.extend(configuration.split_whitespace().map(|argument| argument.to_string()));
+        let mut configuration_options = match parse_options(configuration_arguments) {
+            Ok(configuration_options) => configuration_options,
+            Err(message) => {
+                eprintln!("In configuration \"{}\": {}", configuration, message);
+                process::exit(1);
+            }
+        };
+        configuration_options.trials = Some(configuration_options.trials.unwrap_or(10));
+
+        let summary = experiment_summary(configuration_options);
+        println!("Configuration \"{}\":\n{}", configuration, summary.report());
+        names.push(configuration.clone());
+        summaries.push(summary);
+    }
+
+    let fitnesses: Vec<Vec<f32>> = summaries.iter()
+        .map(|summary| summary.trials.iter().map(|trial| trial.best_fitness()).collect())
+        .collect();
+    let mut report = comparison_report("Final best fitness", &names, &fitnesses, true, options.alpha);
+
+    // Trials that missed the target never get there, which ranks them last.
+    if summaries.iter().all(|summary| summary.target.is_some()) {
+        let evaluations: Vec<Vec<f32>> = summaries.iter()
+            .map(|summary| summary.trials.iter()
+                 .map(|trial| trial.hit.map_or(f32::INFINITY, |(_, evaluations)| evaluations as f32))
+                 .collect())
+            .collect();
+        report.push_str(&comparison_report("Evaluations to target", &names, &evaluations, false, options.alpha));
+    }
+
+    print!("{}", report);
+    if let Some(ref directory) = options.results {
+        let written = fs::create_dir_all(directory)
+            .and_then(|_| File::create(Path::new(directory).join("comparison.txt")))
+            .and_then(|mut file| file.write_all(report.as_bytes()));
+        if let Err(error) = written {
+            eprintln!("Could not write results to {}: {}", directory, error);
+            process::exit(1);
+        }
+    }
+}
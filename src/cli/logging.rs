@@ -0,0 +1,65 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use log::{self, Log, LevelFilter, Metadata, Record};
+
+use cli::options::Options;
+
+// Writes "[seconds LEVEL target] message" lines to stderr, or to a file.
+struct Logger {
+    level: LevelFilter,
+    file: Option<Mutex<File>>,
+    started: Instant,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let elapsed = self.started.elapsed();
+        let line = format!("[{:>4}.{:03} {:<5} {}] {}\n",
+                           elapsed.as_secs(),
+                           elapsed.subsec_millis(),
+                           record.level(),
+                           record.target(),
+                           record.args());
+        match self.file {
+            Some(ref file) => { let _ = file.lock().unwrap().write_all(line.as_bytes()); },
+            None => { let _ = io::stderr().write_all(line.as_bytes()); },
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(ref file) = self.file {
+            let _ = file.lock().unwrap().flush();
+        }
+    }
+}
+
+// Info by default, one level more per -v, errors only with -q. The dashboard
+// owns the terminal, so it only leaves room for warnings unless asked for more.
+pub fn init_logging(options: &Options) -> Result<(), String> {
+    let levels = [LevelFilter::Info, LevelFilter::Debug, LevelFilter::Trace];
+    let level = match options.verbosity {
+        None => LevelFilter::Error,
+        Some(0) if options.dashboard && options.log_file.is_none() => LevelFilter::Warn,
+        Some(verbosity) => levels[verbosity.min(levels.len() - 1)],
+    };
+    let file = match options.log_file {
+        Some(ref path) => Some(Mutex::new(File::create(path)
+            .map_err(|error| format!("could not create log file {}: {}", path, error))?)),
+        None => None,
+    };
+
+    let logger = Logger { level: level, file: file, started: Instant::now() };
+    log::set_logger(Box::leak(Box::new(logger))).map_err(|error| error.to_string())?;
+    log::set_max_level(level);
+    Ok(())
+}
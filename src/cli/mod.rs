@@ -0,0 +1,6 @@
+pub mod options;
+pub mod logging;
+pub mod operators;
+pub mod run;
+pub mod problems;
+pub mod experiments;
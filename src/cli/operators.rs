@@ -0,0 +1,145 @@
+use std::process;
+
+use num::Num;
+
+use genetic_thing::genetic::crossover::{one_point_crossover,
+                                        one_point_crossover_3,
+                                        uniform_crossover,
+                                        uniform_average_crossover,
+                                        uniform_crossover_3,
+                                        partially_matched_crossover,
+                                        order_crossover,
+                                        cycle_crossover,
+                                        edge_recombination_crossover,
+                                        position_based_crossover};
+use genetic_thing::genetic::mutation::{bit_flip,
+                                       swap_position,
+                                       inversion_mutation,
+                                       scramble_mutation,
+                                       insertion_mutation,
+                                       two_opt_mutation,
+                                       random_int,
+                                       random_real,
+                                       delta_mutation,
+                                       gaussian_mutation};
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::portfolio::{Portfolio, OperatorSelection, CrossoverFunction, MutationFunction};
+
+use cli::options::Options;
+
+// Builds a portfolio from "name[:weight],..." with the operators `lookup` knows.
+fn parse_portfolio<F: Copy>(spec: &str,
+                            selection: OperatorSelection,
+                            lookup: fn(&str) -> Option<(&'static str, F)>) -> Result<Portfolio<F>, String> {
+    let mut portfolio = Portfolio::new(selection);
+    for entry in spec.split(',') {
+        let mut parts = entry.splitn(2, ':');
+        let name = parts.next().unwrap_or("");
+        let weight = match parts.next() {
+            Some(weight) => weight.parse::<f32>().map_err(|_| format!("bad weight in {}", entry))?,
+            None => 1.0,
+        };
+        let (name, function) = lookup(name).ok_or(format!("unknown operator {}", name))?;
+        portfolio = portfolio.with(name, function, weight)?;
+    }
+    Ok(portfolio)
+}
+
+pub fn bit_string_crossover(name: &str) -> Option<(&'static str, CrossoverFunction<u8>)> {
+    match name {
+        "one-point" => Some(("one-point", one_point_crossover)),
+        "one-point-3" => Some(("one-point-3", one_point_crossover_3)),
+        "uniform" => Some(("uniform", uniform_crossover)),
+        "uniform-3" => Some(("uniform-3", uniform_crossover_3)),
+        _ => None,
+    }
+}
+
+pub fn bit_string_mutation(name: &str) -> Option<(&'static str, MutationFunction<u8>)> {
+    match name {
+        "bit-flip" => Some(("bit-flip", bit_flip)),
+        _ => None,
+    }
+}
+
+pub fn permutation_crossover(name: &str) -> Option<(&'static str, CrossoverFunction<i32>)> {
+    match name {
+        "pmx" => Some(("pmx", partially_matched_crossover)),
+        "order" => Some(("order", order_crossover)),
+        "cycle" => Some(("cycle", cycle_crossover)),
+        "edge" => Some(("edge", edge_recombination_crossover)),
+        "position" => Some(("position", position_based_crossover)),
+        _ => None,
+    }
+}
+
+pub fn permutation_mutation(name: &str) -> Option<(&'static str, MutationFunction<i32>)> {
+    match name {
+        "swap" => Some(("swap", swap_position)),
+        "inversion" => Some(("inversion", inversion_mutation)),
+        "scramble" => Some(("scramble", scramble_mutation)),
+        "insertion" => Some(("insertion", insertion_mutation)),
+        "2opt" => Some(("2opt", two_opt_mutation)),
+        _ => None,
+    }
+}
+
+pub fn integer_crossover(name: &str) -> Option<(&'static str, CrossoverFunction<i32>)> {
+    match name {
+        "one-point" => Some(("one-point", one_point_crossover)),
+        "one-point-3" => Some(("one-point-3", one_point_crossover_3)),
+        "uniform" => Some(("uniform", uniform_crossover)),
+        "uniform-3" => Some(("uniform-3", uniform_crossover_3)),
+        _ => None,
+    }
+}
+
+pub fn integer_mutation(name: &str) -> Option<(&'static str, MutationFunction<i32>)> {
+    match name {
+        "random" => Some(("random", random_int)),
+        _ => None,
+    }
+}
+
+pub fn real_crossover(name: &str) -> Option<(&'static str, CrossoverFunction<f32>)> {
+    match name {
+        "one-point" => Some(("one-point", one_point_crossover)),
+        "uniform" => Some(("uniform", uniform_crossover)),
+        "average" => Some(("average", uniform_average_crossover)),
+        _ => None,
+    }
+}
+
+pub fn real_mutation(name: &str) -> Option<(&'static str, MutationFunction<f32>)> {
+    match name {
+        "gaussian" => Some(("gaussian", gaussian_mutation)),
+        "delta" => Some(("delta", delta_mutation)),
+        "random" => Some(("random", random_real)),
+        _ => None,
+    }
+}
+
+// Installs the --crossover and --mutation portfolios, if given.
+pub fn configure_portfolios<T>(population: &mut Population<T>,
+                               options: &Options,
+                               crossovers: fn(&str) -> Option<(&'static str, CrossoverFunction<T>)>,
+                               mutations: fn(&str) -> Option<(&'static str, MutationFunction<T>)>)
+    where T: Copy + PartialOrd + Num
+{
+    if let Some(ref spec) = options.crossovers {
+        let installed = parse_portfolio(spec, options.operator_selection, crossovers)
+            .and_then(|portfolio| population.set_crossover_portfolio(portfolio));
+        if let Err(message) = installed {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+    if let Some(ref spec) = options.mutations {
+        let installed = parse_portfolio(spec, options.operator_selection, mutations)
+            .and_then(|portfolio| population.set_mutation_portfolio(portfolio));
+        if let Err(message) = installed {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+}
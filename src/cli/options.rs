@@ -0,0 +1,414 @@
+use std::process;
+
+use genetic_thing::genetic::local_search::WriteBack;
+use genetic_thing::genetic::portfolio::OperatorSelection;
+use genetic_thing::genetic::problems::maze::MoveEncoding;
+use genetic_thing::genetic::replacement::{ReplacementMode, ReplacementPolicy, Elitism, SurvivorSelection, GenerationGap,
+                                          ParentSelection};
+use genetic_thing::genetic::sweep::{Parameter, Values, Strategy};
+
+#[derive(Clone)]
+pub struct Options {
+    pub problem: String,
+    pub total_generations: usize,
+    pub population_size: Option<usize>,
+    pub crossover_probability: Option<f32>,
+    pub mutation_probability: Option<f32>,
+    pub crowding_factor: Option<usize>,
+    pub maze_path: Option<String>,
+    pub move_encoding: MoveEncoding,
+    pub board_size: usize,
+    pub free_queens: bool,
+    pub function: String,
+    pub dimensions: Option<usize>,
+    pub k: Option<usize>,
+    pub seed: usize,
+    pub instance: Option<String>,
+    pub penalty: bool,
+    pub patterns: Vec<String>,
+    pub noise: f32,
+    pub local_search_probability: f32,
+    pub local_search_budget: usize,
+    pub local_search_method: Option<String>,
+    pub write_back: WriteBack,
+    pub scaling: Option<String>,
+    pub scaling_parameter: Option<f32>,
+    pub parent_selection: Option<ParentSelection>,
+    pub generation_gap: Option<GenerationGap>,
+    pub genealogy: Option<String>,
+    pub crossovers: Option<String>,
+    pub mutations: Option<String>,
+    pub operator_selection: OperatorSelection,
+    pub replacement: ReplacementMode,
+    pub elitism: Option<Elitism>,
+    pub survivor_selection: SurvivorSelection,
+    pub trials: Option<usize>,
+    pub threads: usize,
+    pub target: Option<f32>,
+    pub results: Option<String>,
+    pub sweep: Vec<Parameter>,
+    pub sweep_strategy: Strategy,
+    pub compare: Vec<String>,
+    pub alpha: f32,
+    pub stop_at_target: bool,
+    pub stagnation: Option<usize>,
+    pub dashboard: bool,
+    // Extra -v flags given, or None for -q.
+    pub verbosity: Option<usize>,
+    pub log_file: Option<String>,
+    pub render: Option<String>,
+    pub render_generation: Option<usize>,
+    pub output: Option<String>,
+}
+
+pub const USAGE: &'static str = "Usage: genetic-thing [options]
+    --problem deceptive|maze|queens|tsp|benchmark|binary|knapsack|maxsat|coloring|pattern
+                                      problem to evolve (default: deceptive)
+    --generations N                   number of generations (default: 10000)
+    --population-size N               population size (default: the problem's, usually 100)
+    --crossover-probability P         crossover probability (default: the problem's)
+    --mutation-probability P          per-gene mutation probability (default: the problem's)
+    --crowding-factor N               crowding factor (default: the problem's)
+    --maze FILE                       maze file for the maze problem
+    --encoding relative|absolute      maze move encoding (default: relative)
+    --queens N                        board size for the queens problem (default: 8)
+    --board-encoding permutation|integer
+                                      queens genome encoding (default: permutation)
+    --function NAME                   benchmark to use: a continuous one to minimize
+                                      (default: rastrigin), or for binary one of onemax,
+                                      leading-ones, trap, loose-trap, royal-road, nk, hiff
+    --dimensions N                    benchmark dimensionality or binary genome length
+    --k K                             trap order, NK epistasis, royal road block size
+                                      or number of colors
+    --seed S                          seed of the generated NK landscape, and of the
+                                      first trial with --trials (default: 0)
+    --instance FILE                   TSPLIB, knapsack, DIMACS CNF or DIMACS .col instance;
+                                      a TSPLIB .opt.tour next to it is loaded as well
+    --penalty                         penalize overweight knapsacks instead of repairing
+    --pattern FILE                    target bitmap (PBM or text), repeat for several
+    --noise P                         chance of flipping each target pixel per evaluation
+    --local-search P                  improve each offspring with probability P (memetic)
+    --local-search-budget N           evaluations per local search (default: 50)
+    --local-search-method NAME        nelder-mead or coordinate for benchmarks (default:
+                                      nelder-mead), swap or 2opt for queens (default: swap)
+                                      and tsp (default: 2opt)
+    --baldwinian                      keep learned fitness but not the improved genome
+    --scaling linear|sigma|power|rank|window
+                                      fitness scaling before selection (default: the
+                                      problem's, linear when it scales at all)
+    --scaling-parameter X             final c for linear (default: 2), c for sigma
+                                      (default: 2), exponent for power (default: 1.005),
+                                      pressure for rank (default: 1.5), generations for
+                                      window (default: 5)
+    --selection tournament|roulette   parent selection: the fittest of 4 random individuals,
+                                      or fitness-proportional, where scaling sets the
+                                      pressure (default: tournament)
+    --generation-gap progressive|adaptive|F|P:F,P:F...
+                                      fraction of the population replaced per generation:
+                                      growing with progress, following diversity, fixed,
+                                      or F from progress P on (default: the problem's)
+    --steady-state worst|random|oldest|similar|parent
+                                      breed a few offspring at a time, replacing the
+                                      chosen individual, instead of whole generations
+    --offspring 1|2                   offspring per step, with --steady-state (default: 2)
+    --elites N|F                      keep the N fittest, or a fraction F of the population
+                                      (default: the problem's, usually 1)
+    --plus LAMBDA                     (mu + lambda) survivor selection with LAMBDA offspring
+    --comma LAMBDA                    (mu, lambda) survivor selection, LAMBDA >= population
+    --crossover NAME[:WEIGHT],...     crossover portfolio: one-point, one-point-3, uniform,
+                                      uniform-3 for bit strings (deceptive, binary,
+                                      knapsack, maxsat, pattern) and integers (maze,
+                                      coloring, integer queens); pmx, order, cycle, edge,
+                                      position for permutations (queens, tsp); one-point,
+                                      uniform, average for benchmarks
+    --mutation NAME[:WEIGHT],...      mutation portfolio: bit-flip for bit strings; random
+                                      for integers; swap, inversion, scramble, insertion,
+                                      2opt for permutations; gaussian, delta, random
+                                      for benchmarks
+    --operator-selection fixed|matching|pursuit|ucb
+                                      how portfolios pick operators (default: fixed weights)
+    --genealogy FILE                  track every individual's parents and operators and
+                                      write the ancestry of the final best to FILE, as
+                                      Graphviz DOT if it ends in .dot, JSON otherwise
+    --trials N                        run N independently seeded trials and report
+                                      aggregate statistics
+    --threads N                       worker threads for --trials (default: 4)
+    --target F                        fitness that counts as a success (default: the
+                                      problem's optimum, if known)
+    --results DIR                     write per-trial and aggregate CSVs to DIR
+    --sweep NAME=A,B,...|NAME=A..B[:N]
+                                      sweep population-size, crossover-probability,
+                                      mutation-probability or crowding-factor over a list
+                                      or a range (N grid points, default: 5), repeat for
+                                      several; runs --trials (default: 5) per setting and
+                                      ranks the settings
+    --sweep-strategy grid|random:N|lhs:N
+                                      every combination, N uniform draws, or N Latin
+                                      hypercube draws (default: grid)
+    --compare OPTIONS                 run --trials (default: 10) of the configuration given by
+                                      these extra options, repeat for each configuration to
+                                      compare; reports Mann-Whitney, Wilcoxon, Kruskal-Wallis
+                                      with Dunn's post-hoc test and A12 on final fitness and
+                                      evaluations to target
+    --alpha A                         significance level for --compare (default: 0.05)
+    --stop-at-target                  end a run as soon as it reaches --target
+    --stagnation N                    end a run after N generations without a new best
+    --dashboard                       show a live terminal dashboard; logs below warnings are
+                                      hidden unless -v or --log-file is given
+    -v, -vv, -vvv                     log more: every generation, then every operator application
+    -q, --quiet                       log errors only
+    --log-file FILE                   write the log to FILE instead of stderr
+    --render ascii|svg                render the best individual (maze, queens, pattern)
+    --render-generation N             generation to render (default: last)
+    --output FILE                     write the rendering to FILE instead of stdout";
+
+pub fn parse_options(arguments: Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        problem: "deceptive".to_string(),
+        total_generations: 10000,
+        population_size: None,
+        crossover_probability: None,
+        mutation_probability: None,
+        crowding_factor: None,
+        maze_path: None,
+        move_encoding: MoveEncoding::RelativeTurn,
+        board_size: 8,
+        free_queens: false,
+        function: "rastrigin".to_string(),
+        dimensions: None,
+        k: None,
+        seed: 0,
+        instance: None,
+        penalty: false,
+        patterns: Vec::new(),
+        noise: 0.0,
+        local_search_probability: 0.0,
+        local_search_budget: 50,
+        local_search_method: None,
+        write_back: WriteBack::Lamarckian,
+        scaling: None,
+        scaling_parameter: None,
+        parent_selection: None,
+        generation_gap: None,
+        genealogy: None,
+        crossovers: None,
+        mutations: None,
+        operator_selection: OperatorSelection::Fixed,
+        replacement: ReplacementMode::Generational,
+        elitism: None,
+        survivor_selection: SurvivorSelection::Replace,
+        trials: None,
+        threads: 4,
+        target: None,
+        results: None,
+        sweep: Vec::new(),
+        sweep_strategy: Strategy::Grid,
+        compare: Vec::new(),
+        alpha: 0.05,
+        stop_at_target: false,
+        stagnation: None,
+        dashboard: false,
+        verbosity: Some(0),
+        log_file: None,
+        render: None,
+        render_generation: None,
+        output: None,
+    };
+
+    // Only applied once --steady-state is known, whatever the order
+    let mut offspring_per_step = None;
+    let mut arguments = arguments.into_iter();
+    while let Some(flag) = arguments.next() {
+        let mut value = || arguments.next().ok_or(format!("{} expects a value", flag));
+        match flag.as_str() {
+            "--problem" => options.problem = value()?,
+            "--generations" => options.total_generations = parse_value(&value()?)?,
+            "--population-size" => options.population_size = Some(parse_value(&value()?)?),
+            "--crossover-probability" => options.crossover_probability = Some(value()?.parse::<f32>()
+                .map_err(|_| "--crossover-probability expects a probability")?),
+            "--mutation-probability" => options.mutation_probability = Some(value()?.parse::<f32>()
+                .map_err(|_| "--mutation-probability expects a probability")?),
+            "--crowding-factor" => options.crowding_factor = Some(parse_value(&value()?)?),
+            "--maze" => options.maze_path = Some(value()?),
+            "--encoding" => options.move_encoding = match value()?.as_str() {
+                "relative" => MoveEncoding::RelativeTurn,
+                "absolute" => MoveEncoding::AbsoluteDirection,
+                other => return Err(format!("unknown move encoding {}", other)),
+            },
+            "--queens" => options.board_size = parse_value(&value()?)?,
+            "--board-encoding" => options.free_queens = match value()?.as_str() {
+                "permutation" => false,
+                "integer" => true,
+                other => return Err(format!("unknown board encoding {}", other)),
+            },
+            "--function" => options.function = value()?,
+            "--dimensions" => options.dimensions = Some(parse_value(&value()?)?),
+            "--k" => options.k = Some(parse_value(&value()?)?),
+            "--seed" => options.seed = parse_value(&value()?)?,
+            "--instance" => options.instance = Some(value()?),
+            "--penalty" => options.penalty = true,
+            "--pattern" => options.patterns.push(value()?),
+            "--noise" => options.noise = value()?.parse::<f32>().map_err(|_| "--noise expects a probability")?,
+            "--local-search" => options.local_search_probability = value()?.parse::<f32>()
+                .map_err(|_| "--local-search expects a probability")?,
+            "--local-search-budget" => options.local_search_budget = parse_value(&value()?)?,
+            "--local-search-method" => options.local_search_method = Some(value()?),
+            "--baldwinian" => options.write_back = WriteBack::Baldwinian,
+            "--scaling" => options.scaling = Some(value()?),
+            "--scaling-parameter" => options.scaling_parameter = Some(value()?.parse::<f32>()
+                .map_err(|_| "--scaling-parameter expects a number")?),
+            "--selection" => options.parent_selection = Some(match value()?.as_str() {
+                "tournament" => ParentSelection::Tournament(4),
+                "roulette" => ParentSelection::Roulette,
+                other => return Err(format!("unknown parent selection {}", other)),
+            }),
+            "--generation-gap" => options.generation_gap = Some(parse_generation_gap(&value()?)?),
+            "--steady-state" => {
+                let policy = match value()?.as_str() {
+                    "worst" => ReplacementPolicy::Worst,
+                    "random" => ReplacementPolicy::Random,
+                    "oldest" => ReplacementPolicy::Oldest,
+                    "similar" => ReplacementPolicy::MostSimilar,
+                    "parent" => ReplacementPolicy::Parent,
+                    other => return Err(format!("unknown replacement policy {}", other)),
+                };
+                options.replacement = ReplacementMode::SteadyState { offspring_per_step: 2, policy: policy };
+            },
+            "--elites" => {
+                let elites = value()?;
+                options.elitism = Some(if elites.contains('.') {
+                    Elitism::Fraction(elites.parse::<f32>().map_err(|_| "--elites expects a count or a fraction")?)
+                } else {
+                    Elitism::Count(parse_value(&elites)?)
+                });
+            },
+            "--plus" => options.survivor_selection = SurvivorSelection::Plus { offspring: parse_value(&value()?)? },
+            "--comma" => options.survivor_selection = SurvivorSelection::Comma { offspring: parse_value(&value()?)? },
+            "--offspring" => {
+                let offspring = parse_value(&value()?)?;
+                if offspring != 1 && offspring != 2 {
+                    return Err("--offspring expects 1 or 2".to_string());
+                }
+                offspring_per_step = Some(offspring);
+            },
+            "--genealogy" => options.genealogy = Some(value()?),
+            "--crossover" => options.crossovers = Some(value()?),
+            "--mutation" => options.mutations = Some(value()?),
+            "--operator-selection" => options.operator_selection = match value()?.as_str() {
+                "fixed" => OperatorSelection::Fixed,
+                "matching" => OperatorSelection::ProbabilityMatching { min_probability: 0.05, adaptation_rate: 0.3 },
+                "pursuit" => OperatorSelection::AdaptivePursuit { min_probability: 0.05,
+                                                                   adaptation_rate: 0.3,
+                                                                   learning_rate: 0.3 },
+                "ucb" => OperatorSelection::Ucb { exploration: 1.0 },
+                other => return Err(format!("unknown operator selection {}", other)),
+            },
+            "--trials" => options.trials = Some(parse_value(&value()?)?),
+            "--threads" => options.threads = parse_value(&value()?)?,
+            "--target" => options.target = Some(value()?.parse::<f32>().map_err(|_| "--target expects a fitness")?),
+            "--results" => options.results = Some(value()?),
+            "--sweep" => options.sweep.push(parse_sweep_parameter(&value()?)?),
+            "--sweep-strategy" => options.sweep_strategy = parse_sweep_strategy(&value()?)?,
+            "--compare" => options.compare.push(value()?),
+            "--alpha" => options.alpha = value()?.parse::<f32>().map_err(|_| "--alpha expects a significance level")?,
+            "--stop-at-target" => options.stop_at_target = true,
+            "--stagnation" => options.stagnation = Some(parse_value(&value()?)?),
+            "--dashboard" => options.dashboard = true,
+            "-v" | "-vv" | "-vvv" => options.verbosity = Some(options.verbosity.unwrap_or(0) + flag.len() - 1),
+            "-q" | "--quiet" => options.verbosity = None,
+            "--log-file" => options.log_file = Some(value()?),
+            "--render" => options.render = match value()?.as_str() {
+                format @ "ascii" | format @ "svg" => Some(format.to_string()),
+                other => return Err(format!("unknown render format {}", other)),
+            },
+            "--render-generation" => options.render_generation = Some(parse_value(&value()?)?),
+            "--output" => options.output = Some(value()?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            },
+            other => return Err(format!("unknown option {}", other)),
+        }
+    }
+
+    if let Some(offspring_per_step) = offspring_per_step {
+        options.replacement = match options.replacement {
+            ReplacementMode::SteadyState { policy, .. } =>
+                ReplacementMode::SteadyState { offspring_per_step: offspring_per_step, policy: policy },
+            ReplacementMode::Generational => return Err("--offspring needs --steady-state".to_string()),
+        };
+    }
+
+    // K = 0 is a valid NK landscape, so --k is checked per problem
+    if options.problem == "coloring" && options.k == Some(0) {
+        return Err("--k needs at least one color".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_sweep_parameter(spec: &str) -> Result<Parameter, String> {
+    let mut parts = spec.splitn(2, '=');
+    let name = parts.next().unwrap_or("");
+    let values = parts.next().ok_or(format!("--sweep expects NAME=VALUES, got {}", spec))?;
+    if !SWEEP_PARAMETERS.contains(&name) {
+        return Err(format!("cannot sweep {}, expected one of: {}", name, SWEEP_PARAMETERS.join(", ")));
+    }
+    let parse_number = |number: &str| number.parse::<f32>().map_err(|_| format!("expected a number, got {}", number));
+
+    let values = if values.contains("..") {
+        let mut bounds = values.splitn(2, "..");
+        let start = parse_number(bounds.next().unwrap_or(""))?;
+        let mut end_and_steps = bounds.next().unwrap_or("").splitn(2, ':');
+        let end = parse_number(end_and_steps.next().unwrap_or(""))?;
+        let steps = match end_and_steps.next() {
+            Some(steps) => parse_value(steps)?,
+            None => 5,
+        };
+        Values::Range { start: start, end: end, steps: steps }
+    } else {
+        Values::List(values.split(',').map(|value| parse_number(value)).collect::<Result<Vec<f32>, String>>()?)
+    };
+    Ok(Parameter { name: name.to_string(), values: values })
+}
+
+fn parse_sweep_strategy(value: &str) -> Result<Strategy, String> {
+    let mut parts = value.splitn(2, ':');
+    let strategy = parts.next().unwrap_or("");
+    let mut samples = || parts.next().ok_or(format!("{} expects a number of samples, as in {}:20", strategy, strategy))
+        .and_then(|samples| parse_value(samples));
+    match strategy {
+        "grid" => Ok(Strategy::Grid),
+        "random" => Ok(Strategy::Random { samples: samples()? }),
+        "lhs" => Ok(Strategy::LatinHypercube { samples: samples()? }),
+        other => Err(format!("unknown sweep strategy {}", other)),
+    }
+}
+
+const SWEEP_PARAMETERS: [&'static str; 4] = ["population-size", "crossover-probability",
+                                             "mutation-probability", "crowding-factor"];
+
+fn parse_generation_gap(value: &str) -> Result<GenerationGap, String> {
+    let parse_fraction = |fraction: &str| fraction.parse::<f32>()
+        .map_err(|_| format!("expected a fraction, got {}", fraction));
+
+    match value {
+        "progressive" => Ok(GenerationGap::Progressive),
+        "adaptive" => Ok(GenerationGap::Adaptive { min: 0.2, max: 1.0 }),
+        schedule if schedule.contains(':') => {
+            let mut points = Vec::new();
+            for point in schedule.split(',') {
+                let mut parts = point.splitn(2, ':');
+                let progress = parse_fraction(parts.next().unwrap_or(""))?;
+                let fraction = parse_fraction(parts.next().unwrap_or(""))?;
+                points.push((progress, fraction));
+            }
+            points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            Ok(GenerationGap::Schedule(points))
+        },
+        fraction => Ok(GenerationGap::Fixed(parse_fraction(fraction)?)),
+    }
+}
+
+fn parse_value(value: &str) -> Result<usize, String> {
+    value.parse::<usize>().map_err(|_| format!("expected a number, got {}", value))
+}
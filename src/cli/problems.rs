@@ -0,0 +1,525 @@
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+
+use genetic_thing::genetic::crossover::{one_point_crossover, uniform_crossover, partially_matched_crossover, order_crossover};
+use genetic_thing::genetic::diversity::DiversityMeasure;
+use genetic_thing::genetic::fitness::deceptive_f3s;
+use genetic_thing::genetic::helpers::Range;
+use genetic_thing::genetic::helpers::{hamming_distance, euclidean_distance_int, euclidean_distance_float};
+use genetic_thing::genetic::local_search::{BitFlipHillClimbing, SwapDescent, TwoOptDescent, CoordinateDescent, NelderMead};
+use genetic_thing::genetic::mutation::{bit_flip, swap_position, two_opt_mutation, random_int, gaussian_mutation};
+use genetic_thing::genetic::plot::show_plots;
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::problems::{Problem, Render};
+use genetic_thing::genetic::problems::benchmarks::{benchmark, benchmarks, Benchmark};
+use genetic_thing::genetic::problems::binary::{OneMax, LeadingOnes, Trap, Linkage, RoyalRoad, NkLandscape, Hiff};
+use genetic_thing::genetic::problems::coloring::GraphColoring;
+use genetic_thing::genetic::problems::knapsack::{Knapsack, ConstraintHandling};
+use genetic_thing::genetic::problems::maze::Maze;
+use genetic_thing::genetic::problems::pattern::{Bitmap, PatternMatching};
+use genetic_thing::genetic::problems::queens::NQueens;
+use genetic_thing::genetic::problems::sat::MaxSat;
+use genetic_thing::genetic::problems::tsp::Tsp;
+
+use cli::operators::{configure_portfolios, bit_string_crossover, bit_string_mutation,
+                     permutation_crossover, permutation_mutation, integer_crossover, integer_mutation,
+                     real_crossover, real_mutation};
+use cli::options::Options;
+use cli::run::{evolve, report_solution, render_best};
+
+pub fn run_deceptive(options: &Options) {
+    let mut population = build_deceptive(options);
+
+    debug!("initial population");
+    population.log_individuals();
+
+    evolve(&mut population, options, None);
+    show_plots(&population);
+}
+
+pub fn build_deceptive(options: &Options) -> Population<u8> {
+    let population_size = options.population_size.unwrap_or(50);
+    let genome_size = 30;
+
+    let crossover_probability = options.crossover_probability.unwrap_or(0.98);
+    let mutation_probability = options.mutation_probability.unwrap_or(0.001);
+    
+    let has_elitism = true;
+    let has_scaling = true;
+    let has_generation_gap = true;
+    let has_fitness_sharing = true;
+    let crowding_factor = options.crowding_factor.unwrap_or(20);
+
+    let distance_function = hamming_distance;
+    
+    let fitness_function = deceptive_f3s;
+    let mutation_function = bit_flip;
+    
+    let mut population = Population::<u8>::new(population_size,
+                                               genome_size,
+                                               crossover_probability,
+                                               mutation_probability,
+                                               Range::new(0, 1),
+                                               has_elitism,
+                                               has_scaling,
+                                               has_generation_gap,
+                                               has_fitness_sharing,
+                                               crowding_factor,
+                                               distance_function,
+                                               fitness_function,
+                                               one_point_crossover,
+                                               mutation_function);
+    population.set_diversity_measure(DiversityMeasure::Hamming);
+    configure_portfolios(&mut population, options, bit_string_crossover, bit_string_mutation);
+    population
+}
+
+pub fn run_maze(options: &Options) {
+    let maze = load_maze(options);
+    let mut population = build_maze(&maze, options);
+
+    let rendered_maze = maze.clone();
+    evolve(&mut population, options, Some(Box::new(move |genome| rendered_maze.render_ascii(genome))));
+    render_best(&maze, &population, options);
+    show_plots(&population);
+}
+
+pub fn load_maze(options: &Options) -> Maze {
+    let maze = match options.maze_path {
+        Some(ref path) => Maze::load(path).unwrap_or_else(|error| {
+            eprintln!("Could not load maze {}: {}", path, error);
+            process::exit(1);
+        }),
+        None => Maze::default_maze(),
+    };
+    maze.with_encoding(options.move_encoding)
+}
+
+pub fn build_maze(maze: &Maze, options: &Options) -> Population<i32> {
+    let evaluated_maze = maze.clone();
+
+    let mut population = Population::<i32>::new(options.population_size.unwrap_or(100),
+                                                 maze.width * maze.height / 2,
+                                                 options.crossover_probability.unwrap_or(0.9),
+                                                 options.mutation_probability.unwrap_or(0.01),
+                                                 Range::new(0, maze.encoding.max_gene()),
+                                                 true,
+                                                 true,
+                                                 false,
+                                                 false,
+                                                 options.crowding_factor.unwrap_or(1),
+                                                 euclidean_distance_int,
+                                                 move |genome, _| evaluated_maze.fitness(genome),
+                                                 one_point_crossover,
+                                                 random_int);
+    configure_portfolios(&mut population, options, integer_crossover, integer_mutation);
+    population
+}
+
+pub fn run_queens(options: &Options) {
+    let board = Rc::new(NQueens::new(options.board_size));
+    let mut population = build_queens(board.clone(), options);
+
+    let rendered_board = board.clone();
+    evolve(&mut population, options, Some(Box::new(move |genome| rendered_board.render_ascii(genome))));
+    println!("Distinct solutions found: {}", board.distinct_solutions());
+    render_best(&*board, &population, options);
+    show_plots(&population);
+}
+
+pub fn build_queens(board: Rc<NQueens>, options: &Options) -> Population<i32> {
+    let evaluated_board = board.clone();
+    let fitness_function = move |genome: &Vec<i32>, _: &Range<i32>| evaluated_board.fitness(genome);
+    let size = options.board_size;
+
+    let mut population = if options.free_queens {
+        Population::<i32>::new(options.population_size.unwrap_or(100),
+                               size,
+                               options.crossover_probability.unwrap_or(0.9),
+                               options.mutation_probability.unwrap_or(0.05),
+                               board.range(),
+                               true,
+                               true,
+                               false,
+                               false,
+                               options.crowding_factor.unwrap_or(1),
+                               euclidean_distance_int,
+                               fitness_function,
+                               one_point_crossover,
+                               random_int)
+    } else {
+        Population::<i32>::new_ordered(options.population_size.unwrap_or(100),
+                                       size,
+                                       options.crossover_probability.unwrap_or(0.9),
+                                       options.mutation_probability.unwrap_or(0.05),
+                                       board.range(),
+                                       true,
+                                       true,
+                                       false,
+                                       false,
+                                       options.crowding_factor.unwrap_or(1),
+                                       euclidean_distance_int,
+                                       fitness_function,
+                                       partially_matched_crossover,
+                                       swap_position)
+    };
+    if options.free_queens {
+        configure_portfolios(&mut population, options, integer_crossover, integer_mutation);
+    } else {
+        configure_portfolios(&mut population, options, permutation_crossover, permutation_mutation);
+    }
+    if options.local_search_probability > 0.0 {
+        if options.free_queens {
+            eprintln!("Local search is only available for the permutation encoding");
+            process::exit(1);
+        }
+        set_permutation_local_search(&mut population, options, "swap");
+    }
+    population
+}
+
+fn set_permutation_local_search(population: &mut Population<i32>, options: &Options, default_method: &str) {
+    match options.local_search_method.as_ref().map_or(default_method, |method| method.as_str()) {
+        "swap" => population.set_local_search(SwapDescent,
+                                              options.local_search_probability,
+                                              options.local_search_budget,
+                                              options.write_back),
+        "2opt" => population.set_local_search(TwoOptDescent,
+                                              options.local_search_probability,
+                                              options.local_search_budget,
+                                              options.write_back),
+        other => {
+            eprintln!("Unknown local search {} for permutations", other);
+            process::exit(1);
+        }
+    }
+}
+
+pub fn run_tsp(options: &Options) {
+    let tsp = Rc::new(load_tsp(options));
+    let mut population = build_tsp(tsp.clone(), options);
+
+    evolve(&mut population, options, None);
+    report_solution(&*tsp, &population);
+    if let Some(best_individual) = population.best_individual_in_generation.last() {
+        let length = tsp.tour_length(&best_individual.genome);
+        match (tsp.optimal_length(), tsp.gap(&best_individual.genome)) {
+            (Some(optimal), Some(gap)) =>
+                println!("{}: tour length {} (optimal: {}, gap {:.2}%)", tsp.name, length, optimal, gap),
+            _ => println!("{}: tour length {}", tsp.name, length),
+        }
+    }
+    show_plots(&population);
+}
+
+// Loads the TSPLIB instance, and its optimal tour when a `.opt.tour` file
+// sits next to it.
+pub fn load_tsp(options: &Options) -> Tsp {
+    let path = options.instance.clone().unwrap_or("data/tsp/burma14.tsp".to_string());
+    let mut tsp = Tsp::load(&path).unwrap_or_else(|error| {
+        eprintln!("Could not load TSP instance {}: {}", path, error);
+        process::exit(1);
+    });
+    if path.ends_with(".tsp") {
+        let tour_path = format!("{}.opt.tour", &path[..path.len() - 4]);
+        if Path::new(&tour_path).exists() {
+            if let Err(error) = tsp.load_optimal_tour(&tour_path) {
+                eprintln!("Could not load optimal tour {}: {}", tour_path, error);
+                process::exit(1);
+            }
+        }
+    }
+    tsp
+}
+
+pub fn build_tsp(tsp: Rc<Tsp>, options: &Options) -> Population<i32> {
+    let evaluated_tsp = tsp.clone();
+
+    let mut population = Population::<i32>::new_ordered(options.population_size.unwrap_or(100),
+                                                        tsp.dimension,
+                                                        options.crossover_probability.unwrap_or(0.9),
+                                                        options.mutation_probability.unwrap_or(0.05),
+                                                        Range::new(0, tsp.dimension as i32 - 1),
+                                                        true,
+                                                        true,
+                                                        false,
+                                                        false,
+                                                        options.crowding_factor.unwrap_or(1),
+                                                        euclidean_distance_int,
+                                                        move |genome, _| evaluated_tsp.fitness(genome),
+                                                        order_crossover,
+                                                        two_opt_mutation);
+    configure_portfolios(&mut population, options, permutation_crossover, permutation_mutation);
+    if options.local_search_probability > 0.0 {
+        set_permutation_local_search(&mut population, options, "2opt");
+    }
+    population
+}
+
+pub fn run_benchmark(options: &Options) {
+    let benchmark = load_benchmark(options);
+    let mut population = build_benchmark(&benchmark, options);
+
+    evolve(&mut population, options, None);
+    if let Some(best_individual) = population.best_individual_in_generation.last() {
+        println!("{}({}) = {} (known minimum: {:?})",
+                 benchmark.name,
+                 benchmark.dimensions,
+                 benchmark.evaluate(&best_individual.genome),
+                 benchmark.minimum());
+    }
+    show_plots(&population);
+}
+
+pub fn load_benchmark(options: &Options) -> Benchmark {
+    let benchmark = match benchmark(&options.function) {
+        Some(benchmark) => benchmark,
+        None => {
+            let names: Vec<&str> = benchmarks().iter().map(|benchmark| benchmark.name).collect();
+            eprintln!("Unknown benchmark {}, expected one of: {}", options.function, names.join(", "));
+            process::exit(1);
+        }
+    };
+    match options.dimensions {
+        Some(dimensions) => benchmark.with_dimensions(dimensions).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        }),
+        None => benchmark,
+    }
+}
+
+pub fn build_benchmark(benchmark: &Benchmark, options: &Options) -> Population<f32> {
+    let evaluated_benchmark = benchmark.clone();
+
+    let mut population = Population::<f32>::new(options.population_size.unwrap_or(100),
+                                                 benchmark.dimensions,
+                                                 options.crossover_probability.unwrap_or(0.9),
+                                                 options.mutation_probability.unwrap_or(0.05),
+                                                 benchmark.bounds,
+                                                 true,
+                                                 true,
+                                                 false,
+                                                 false,
+                                                 options.crowding_factor.unwrap_or(1),
+                                                 euclidean_distance_float,
+                                                 move |genome, _| evaluated_benchmark.fitness(genome),
+                                                 uniform_crossover,
+                                                 gaussian_mutation);
+    configure_portfolios(&mut population, options, real_crossover, real_mutation);
+    if options.local_search_probability > 0.0 {
+        match options.local_search_method.as_ref().map_or("nelder-mead", |method| method.as_str()) {
+            "nelder-mead" => population.set_local_search(NelderMead { step: 0.05 },
+                                                         options.local_search_probability,
+                                                         options.local_search_budget,
+                                                         options.write_back),
+            "coordinate" => population.set_local_search(CoordinateDescent { step: 0.05 },
+                                                        options.local_search_probability,
+                                                        options.local_search_budget,
+                                                        options.write_back),
+            other => {
+                eprintln!("Unknown local search {} for real genomes", other);
+                process::exit(1);
+            }
+        }
+    }
+    population
+}
+
+pub fn run_bit_strings(options: &Options) {
+    let (problem, length) = bit_string_problem(options);
+    evolve_bit_strings(problem, length, options);
+}
+
+pub fn bit_string_problem(options: &Options) -> (Rc<Problem<u8>>, usize) {
+    match options.problem.as_str() {
+        "knapsack" => knapsack_problem(options),
+        "maxsat" => maxsat_problem(options),
+        _ => binary_problem(options),
+    }
+}
+
+fn binary_problem(options: &Options) -> (Rc<Problem<u8>>, usize) {
+    let length = options.dimensions.unwrap_or(64);
+    let k = options.k.unwrap_or(4);
+    if length == 0 {
+        eprintln!("--dimensions must be at least 1");
+        process::exit(1);
+    }
+    let problem: Result<Rc<Problem<u8>>, String> = match options.function.as_str() {
+        "onemax" => Ok(shared(OneMax { length: length })),
+        "leading-ones" => Ok(shared(LeadingOnes { length: length })),
+        "trap" => Trap::new(length, k, Linkage::Tight).map(shared),
+        "loose-trap" => Trap::new(length, k, Linkage::Loose).map(shared),
+        "royal-road" => RoyalRoad::new(length, options.k.unwrap_or(8)).map(shared),
+        "nk" => NkLandscape::new(length, k, options.seed).map(shared),
+        "hiff" => Hiff::new(length).map(shared),
+        other => Err(format!("Unknown binary problem {}, expected one of: \
+                              onemax, leading-ones, trap, loose-trap, royal-road, nk, hiff", other)),
+    };
+    match problem {
+        Ok(problem) => (problem, length),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+}
+
+fn shared<P: Problem<u8> + 'static>(problem: P) -> Rc<Problem<u8>> {
+    Rc::new(problem)
+}
+
+fn knapsack_problem(options: &Options) -> (Rc<Problem<u8>>, usize) {
+    let path = options.instance.clone().unwrap_or("data/knapsack/p01.txt".to_string());
+    let handling = if options.penalty { ConstraintHandling::Penalty } else { ConstraintHandling::Repair };
+    let knapsack = Knapsack::load(&path).unwrap_or_else(|error| {
+        eprintln!("Could not load knapsack instance {}: {}", path, error);
+        process::exit(1);
+    });
+    let length = knapsack.values.len();
+    (Rc::new(knapsack.with_handling(handling)), length)
+}
+
+fn maxsat_problem(options: &Options) -> (Rc<Problem<u8>>, usize) {
+    let path = options.instance.clone().unwrap_or("data/sat/planted20.cnf".to_string());
+    let formula = MaxSat::load(&path).unwrap_or_else(|error| {
+        eprintln!("Could not load CNF formula {}: {}", path, error);
+        process::exit(1);
+    });
+    let length = formula.variables;
+    (Rc::new(formula), length)
+}
+
+pub fn run_coloring(options: &Options) {
+    let graph = Rc::new(load_coloring(options));
+    let mut population = build_coloring(graph.clone(), options);
+
+    evolve(&mut population, options, None);
+    report_solution(&*graph, &population);
+    if let Some(best_individual) = population.best_individual_in_generation.last() {
+        println!("Colors used: {}, conflicting edges: {}",
+                 graph.colors_used(&best_individual.genome),
+                 graph.conflicts(&best_individual.genome));
+    }
+    show_plots(&population);
+}
+
+pub fn load_coloring(options: &Options) -> GraphColoring {
+    let path = options.instance.clone().unwrap_or("data/coloring/petersen.col".to_string());
+    GraphColoring::load(&path, options.k.unwrap_or(3)).unwrap_or_else(|error| {
+        eprintln!("Could not load graph {}: {}", path, error);
+        process::exit(1);
+    })
+}
+
+pub fn build_coloring(graph: Rc<GraphColoring>, options: &Options) -> Population<i32> {
+    let evaluated_graph = graph.clone();
+
+    let mut population = Population::<i32>::new(options.population_size.unwrap_or(100),
+                                                graph.vertices,
+                                                options.crossover_probability.unwrap_or(0.9),
+                                                options.mutation_probability.unwrap_or(1.0 / graph.vertices as f32),
+                                                graph.range(),
+                                                true,
+                                                true,
+                                                false,
+                                                false,
+                                                options.crowding_factor.unwrap_or(1),
+                                                euclidean_distance_int,
+                                                move |genome, _| evaluated_graph.fitness(genome),
+                                                uniform_crossover,
+                                                random_int);
+    configure_portfolios(&mut population, options, integer_crossover, integer_mutation);
+    population
+}
+
+pub fn run_pattern(options: &Options) {
+    let matcher = Rc::new(load_pattern(options));
+    let mut population = build_pattern(matcher.clone(), options);
+
+    let rendered_matcher = matcher.clone();
+    evolve(&mut population, options, Some(Box::new(move |genome| rendered_matcher.render_ascii(genome))));
+    report_solution(&*matcher, &population);
+    render_best(&*matcher, &population, options);
+    show_plots(&population);
+}
+
+pub fn load_pattern(options: &Options) -> PatternMatching {
+    let mut targets = Vec::new();
+    for path in &options.patterns {
+        targets.push(Bitmap::load(path).unwrap_or_else(|error| {
+            eprintln!("Could not load pattern {}: {}", path, error);
+            process::exit(1);
+        }));
+    }
+    if targets.is_empty() {
+        targets.push(Bitmap::default_pattern());
+    }
+    if targets.iter().any(|target| target.bits.len() != targets[0].bits.len()) {
+        eprintln!("All patterns must have the same number of pixels");
+        process::exit(1);
+    }
+
+    PatternMatching::new(targets).with_noise(options.noise)
+}
+
+pub fn build_pattern(matcher: Rc<PatternMatching>, options: &Options) -> Population<u8> {
+    let evaluated_matcher = matcher.clone();
+    let length = matcher.genome_size();
+
+    let mut population = Population::<u8>::new(options.population_size.unwrap_or(100),
+                                                length,
+                                                options.crossover_probability.unwrap_or(0.9),
+                                                options.mutation_probability.unwrap_or(1.0 / length as f32),
+                                                Range::new(0, 1),
+                                                true,
+                                                true,
+                                                false,
+                                                false,
+                                                options.crowding_factor.unwrap_or(1),
+                                                hamming_distance,
+                                                move |genome, _| evaluated_matcher.fitness(genome),
+                                                uniform_crossover,
+                                                bit_flip);
+    population.set_diversity_measure(DiversityMeasure::Hamming);
+    configure_portfolios(&mut population, options, bit_string_crossover, bit_string_mutation);
+    population
+}
+
+fn evolve_bit_strings(problem: Rc<Problem<u8>>, length: usize, options: &Options) {
+    let mut population = build_bit_strings(problem.clone(), length, options);
+
+    evolve(&mut population, options, None);
+    report_solution(&*problem, &population);
+    show_plots(&population);
+}
+
+pub fn build_bit_strings(problem: Rc<Problem<u8>>, length: usize, options: &Options) -> Population<u8> {
+    let evaluated_problem = problem.clone();
+
+    let mut population = Population::<u8>::new(options.population_size.unwrap_or(100),
+                                                length,
+                                                options.crossover_probability.unwrap_or(0.9),
+                                                options.mutation_probability.unwrap_or(1.0 / length as f32),
+                                                Range::new(0, 1),
+                                                true,
+                                                true,
+                                                false,
+                                                false,
+                                                options.crowding_factor.unwrap_or(1),
+                                                hamming_distance,
+                                                move |genome, _| evaluated_problem.fitness(genome),
+                                                uniform_crossover,
+                                                bit_flip);
+    population.set_diversity_measure(DiversityMeasure::Hamming);
+    configure_portfolios(&mut population, options, bit_string_crossover, bit_string_mutation);
+    if options.local_search_probability > 0.0 {
+        population.set_local_search(BitFlipHillClimbing,
+                                    options.local_search_probability,
+                                    options.local_search_budget,
+                                    options.write_back);
+    }
+    population
+}
@@ -0,0 +1,210 @@
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::Write;
+use std::process;
+
+use num::Num;
+
+use genetic_thing::genetic::dashboard::Dashboard;
+use genetic_thing::genetic::observer::{TargetStop, StagnationStop};
+use genetic_thing::genetic::population::Population;
+use genetic_thing::genetic::portfolio::Portfolio;
+use genetic_thing::genetic::problems::{Problem, Render};
+use genetic_thing::genetic::scaling::{LinearScaling, SigmaTruncation, PowerLaw, RankScaling, Windowing};
+
+use cli::options::Options;
+
+pub fn report_solution<T>(problem: &Problem<T>, population: &Population<T>)
+    where T: Copy + PartialOrd + Num
+{
+    let solved_in = population.best_fitness_in_generation.iter()
+        .position(|&fitness| problem.is_solved(fitness));
+    match (problem.optimum(), solved_in) {
+        (_, Some(generation)) => println!("Optimum reached in generation {} after {} evaluations",
+                                          generation,
+                                          population.evaluations_in_generation[generation]),
+        (Some(optimum), None) => println!("Optimum {} not reached", optimum),
+        (None, None) => println!("Optimum unknown for this instance"),
+    }
+
+    if let Some(best_individual) = population.best_individual_in_generation.last() {
+        println!("Best individual is {}",
+                 if problem.is_feasible(&problem.decode(&best_individual.genome)) { "feasible" } else { "infeasible" });
+    }
+}
+
+// `render` draws a genome for the dashboard, when the problem can.
+pub fn evolve<T>(population: &mut Population<T>, options: &Options, render: Option<Box<Fn(&Vec<T>) -> String>>)
+    where T: Copy + PartialOrd + Num + Debug + 'static
+{
+    configure(population, options);
+    if options.genealogy.is_some() {
+        population.enable_genealogy();
+    }
+
+    if options.dashboard {
+        let dashboard = Dashboard::new(options.total_generations);
+        population.add_observer(match render {
+            Some(render) => dashboard.with_renderer(render),
+            None => dashboard,
+        });
+    }
+    population.run(options.total_generations);
+
+    if let Some(diagnostic) = population.best_individual_diagnostic() {
+        println!("{}", diagnostic);
+    }
+    println!("Fitness evaluations: {}", population.evaluations);
+
+    let diversity = population.diversity_report();
+    println!("Final diversity: mean distance {:.4}, mean locus entropy {:.4} bits, {} unique genotypes",
+             diversity.mean_pairwise_distance,
+             diversity.mean_entropy,
+             diversity.unique_genotypes);
+
+    print_portfolio("crossover", population.crossover_portfolio());
+    print_portfolio("mutation", population.mutation_portfolio());
+    for (operator, statistics) in population.operator_statistics() {
+        println!("Operator {}: {} applications, {:.1}% improved, mean delta {:.4}, {:.1}% survived",
+                 operator,
+                 statistics.applications,
+                 100.0 * statistics.improvement_rate(),
+                 statistics.mean_delta(),
+                 100.0 * statistics.survival_rate());
+    }
+
+    if let Some(ref path) = options.genealogy {
+        write_genealogy(population, path);
+    }
+}
+
+// Applies the generic settings shared by every problem.
+pub fn configure<T>(population: &mut Population<T>, options: &Options)
+    where T: Copy + PartialOrd + Num
+{
+    if let Some(ref scaling) = options.scaling {
+        let parameter = options.scaling_parameter;
+        match scaling.as_str() {
+            "linear" => population.set_scaling(LinearScaling { c_end: parameter.unwrap_or(2.0),
+                                                               ..LinearScaling::default() }),
+            "sigma" => population.set_scaling(SigmaTruncation { c: parameter.unwrap_or(2.0) }),
+            "power" => population.set_scaling(PowerLaw { k: parameter.unwrap_or(1.005) }),
+            "rank" => population.set_scaling(RankScaling { pressure: parameter.unwrap_or(1.5) }),
+            "window" => population.set_scaling(Windowing::new(parameter.map_or(5, |window| window as usize))),
+            other => {
+                eprintln!("Unknown fitness scaling {}", other);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(parent_selection) = options.parent_selection {
+        if let Err(message) = population.set_parent_selection(parent_selection) {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+    if let Some(ref generation_gap) = options.generation_gap {
+        population.set_generation_gap(generation_gap.clone());
+    }
+    if let Err(message) = population.set_replacement(options.replacement) {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+    if let Some(elitism) = options.elitism {
+        population.set_elitism(elitism);
+    }
+    if let Err(message) = population.set_survivor_selection(options.survivor_selection) {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+
+    if options.stop_at_target {
+        match options.target {
+            Some(target) => population.add_observer(TargetStop { target: target }),
+            None => {
+                eprintln!("--stop-at-target needs --target, or a problem with a known optimum under --trials");
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(generations) = options.stagnation {
+        population.add_observer(StagnationStop::new(generations));
+    }
+}
+
+fn print_portfolio<F: Copy>(kind: &str, portfolio: &Portfolio<F>) {
+    if portfolio.len() < 2 {
+        return;
+    }
+    let probabilities: Vec<String> = (0..portfolio.len())
+        .map(|operator| format!("{} {:.2} ({} picks)",
+                                portfolio.name(operator),
+                                portfolio.probabilities()[operator],
+                                portfolio.picks()[operator]))
+        .collect();
+    println!("Final {} probabilities: {}", kind, probabilities.join(", "));
+}
+
+fn write_genealogy<T>(population: &Population<T>, path: &str)
+    where T: Copy + PartialOrd + Num
+{
+    let (genealogy, best_individual) = match (population.genealogy(), population.best_individual_in_generation.last()) {
+        (Some(genealogy), Some(best_individual)) => (genealogy, best_individual),
+        _ => return,
+    };
+
+    let line_of_descent = genealogy.line_of_descent(best_individual.birth);
+    println!("Best individual #{} descends from generation {} in {} births",
+             best_individual.birth,
+             line_of_descent.last().map_or(0, |root| root.lineage.generation),
+             line_of_descent.len().saturating_sub(1));
+    for operator in genealogy.improvements_by_operator() {
+        println!("  {}: improved on its parents {} times in {} applications",
+                 operator.operator, operator.improvements, operator.applications);
+    }
+
+    let ancestry = genealogy.ancestry(best_individual.birth);
+    let contents = if path.ends_with(".dot") { ancestry.to_dot() } else { ancestry.to_json() };
+    let written = File::create(path).and_then(|mut file| file.write_all(contents.as_bytes()));
+    if let Err(error) = written {
+        eprintln!("Could not write {}: {}", path, error);
+    }
+}
+
+pub fn render_best<T, R>(renderer: &R, population: &Population<T>, options: &Options)
+    where T: Copy + PartialOrd + Num, R: Render<T>
+{
+    let format = match options.render {
+        Some(ref format) => format,
+        None => return,
+    };
+
+    let generation = options.render_generation
+        .unwrap_or(population.best_individual_in_generation.len().saturating_sub(1));
+    let best_individual = match population.best_individual_in_generation.get(generation) {
+        Some(individual) => individual,
+        None => {
+            eprintln!("No generation {} to render", generation);
+            return;
+        }
+    };
+
+    // parse_options only lets ascii and svg through
+    let picture = match format.as_str() {
+        "svg" => renderer.render_svg(&best_individual.genome),
+        _ => renderer.render_ascii(&best_individual.genome),
+    };
+
+    match options.output {
+        Some(ref path) => {
+            let written = File::create(path).and_then(|mut file| file.write_all(picture.as_bytes()));
+            if let Err(error) = written {
+                eprintln!("Could not write {}: {}", path, error);
+            }
+        },
+        None => {
+            println!("Best individual of generation {}:", generation);
+            print!("{}", picture);
+        }
+    }
+}
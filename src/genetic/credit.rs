@@ -0,0 +1,35 @@
+// Per-operator credit assignment, see `Population::operator_statistics`.
+// An operator is credited for an offspring when it changed the offspring's
+// genome; one offspring can credit several operators.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperatorStatistics {
+    // Offspring the operator took part in breeding.
+    pub applications: usize,
+    // Of those, offspring that were evaluated, and the ones fitter than the
+    // best of their parents.
+    pub evaluated: usize,
+    pub improvements: usize,
+    // Sum of offspring fitness minus best parent fitness over the evaluated ones.
+    pub total_delta: f32,
+    // Offspring still in the population after the next generation's
+    // replacement. Offspring of the last generation are never counted.
+    pub survivors: usize,
+}
+
+impl OperatorStatistics {
+    pub fn improvement_rate(&self) -> f32 {
+        ratio(self.improvements, self.evaluated)
+    }
+
+    pub fn mean_delta(&self) -> f32 {
+        if self.evaluated == 0 { 0.0 } else { self.total_delta / self.evaluated as f32 }
+    }
+
+    pub fn survival_rate(&self) -> f32 {
+        ratio(self.survivors, self.applications)
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f32 {
+    if total == 0 { 0.0 } else { count as f32 / total as f32 }
+}
@@ -3,12 +3,13 @@ use self::rand::Rng;
 
 
 use genetic::helpers::SimpleStepRange;
+use genetic::random;
 
 extern crate num;
 use self::num::{Num};
 
 use std::ops::{Add, Div};
-use std::fmt::Debug;
+use std::cmp;
 
 /////////////////////////
 // Crossover functions //
@@ -17,7 +18,7 @@ use std::fmt::Debug;
 pub fn one_point_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
     where T: Copy
 {
-    let point = rand::thread_rng().gen_range(1, dad_genome.len() - 1);
+    let point = random::thread_rng().gen_range(1, dad_genome.len() - 1);
 
     let mut boy_genome = dad_genome.clone();
     let mut girl_genome = mom_genome.clone();
@@ -32,7 +33,7 @@ pub fn one_point_crossover_3<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Ve
 {
     let mut point = 1;
     while point % 3 != 0 {
-        point = rand::thread_rng().gen_range(2, dad_genome.len() - 3);
+        point = random::thread_rng().gen_range(2, dad_genome.len() - 3);
     }
     
     let mut boy_genome = dad_genome.clone();
@@ -45,55 +46,229 @@ pub fn one_point_crossover_3<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Ve
 
 
 pub fn partially_matched_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
-    where T: Copy + PartialEq + Debug
+    where T: Copy + PartialEq
 {
-    let start_index = rand::thread_rng().gen_range(1, dad_genome.len() / 2); 
-    let end_index = rand::thread_rng().gen_range(dad_genome.len() / 2, dad_genome.len() - 1);
+    if dad_genome.len() < 2 {
+        return (dad_genome.clone(), mom_genome.clone());
+    }
+    let (start_index, end_index) = random_segment(dad_genome.len());
+
+    let boy_genome = pmx_child(dad_genome, mom_genome, start_index, end_index);
+    let girl_genome = pmx_child(mom_genome, dad_genome, start_index, end_index);
+
+    (boy_genome, girl_genome)
+}
+
+// The segment comes from the donor, every other gene from the receiver. Genes
+// already present in the segment are followed through the segment mapping,
+// which needs at most one hop per segment position.
+fn pmx_child<T>(receiver: &Vec<T>, donor: &Vec<T>, start_index: usize, end_index: usize) -> Vec<T>
+    where T: Copy + PartialEq
+{
+    let mut child = receiver.clone();
+    child[start_index..end_index].copy_from_slice(&donor[start_index..end_index]);
+
+    for i in (0..start_index).chain(end_index..receiver.len()) {
+        let mut gene = receiver[i];
+        while let Some(j) = position_in(&donor[start_index..end_index], gene) {
+            gene = receiver[start_index + j];
+        }
+        child[i] = gene;
+    }
+
+    child
+}
+
+pub fn order_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
+    where T: Copy + PartialEq
+{
+    if dad_genome.len() < 2 {
+        return (dad_genome.clone(), mom_genome.clone());
+    }
+    let (start_index, end_index) = random_segment(dad_genome.len());
+
+    let boy_genome = order_child(dad_genome, mom_genome, start_index, end_index);
+    let girl_genome = order_child(mom_genome, dad_genome, start_index, end_index);
+
+    (boy_genome, girl_genome)
+}
+
+// Keeps the segment of the first parent and fills the remaining positions,
+// starting right after the segment, in the order the genes appear in the other
+// parent from that same point.
+fn order_child<T>(keeper: &Vec<T>, filler: &Vec<T>, start_index: usize, end_index: usize) -> Vec<T>
+    where T: Copy + PartialEq
+{
+    let length = keeper.len();
+    let segment = &keeper[start_index..end_index];
+    let mut child = keeper.clone();
+
+    let mut write_index = end_index % length;
+    for offset in 0..length {
+        let gene = filler[(end_index + offset) % length];
+        if position_in(segment, gene).is_some() {
+            continue;
+        }
+        child[write_index] = gene;
+        write_index = (write_index + 1) % length;
+    }
+
+    child
+}
 
+pub fn cycle_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
+    where T: Copy + PartialEq
+{
+    let length = dad_genome.len();
     let mut boy_genome = dad_genome.clone();
-    let mut girl_genome = mom_genome.clone();    
-    boy_genome[start_index..end_index].copy_from_slice(&mom_genome[start_index..end_index]);
-    girl_genome[start_index..end_index].copy_from_slice(&dad_genome[start_index..end_index]); 
-
-    let mut is_valid_permutation = false;
-    while is_valid_permutation == false {
-        for i in 0..start_index {
-            for j in start_index..end_index {
-                if boy_genome[i] == boy_genome[j] {
-                    boy_genome[i] = girl_genome[j];
-                }
+    let mut girl_genome = mom_genome.clone();
 
-                if girl_genome[i] == girl_genome[j] {
-                    girl_genome[i] = boy_genome[j];
-                }
+    let mut visited = vec![false; length];
+    let mut swap_cycle = false;
+    for cycle_start in 0..length {
+        if visited[cycle_start] {
+            continue;
+        }
+
+        let mut i = cycle_start;
+        while !visited[i] {
+            visited[i] = true;
+            if swap_cycle {
+                boy_genome[i] = mom_genome[i];
+                girl_genome[i] = dad_genome[i];
             }
+            i = match position_in(dad_genome, mom_genome[i]) {
+                Some(next) => next,
+                None => break,
+            };
         }
+        swap_cycle = !swap_cycle;
+    }
 
-        for i in end_index..mom_genome.len() {
-            for j in start_index..end_index {
-                if boy_genome[i] == boy_genome[j] {
-                    boy_genome[i] = girl_genome[j];
-                }
+    (boy_genome, girl_genome)
+}
+
+pub fn edge_recombination_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
+    where T: Copy + PartialEq
+{
+    if dad_genome.len() < 2 {
+        return (dad_genome.clone(), mom_genome.clone());
+    }
+
+    let boy_genome = edge_recombination_child(dad_genome, mom_genome);
+    let girl_genome = edge_recombination_child(mom_genome, dad_genome);
+
+    (boy_genome, girl_genome)
+}
 
-                if girl_genome[i] == girl_genome[j] {
-                    girl_genome[i] = boy_genome[j];
+// Neighbour lists are indexed by the gene position in `first`, so the child is
+// built out of indices and only mapped back to genes at the end.
+fn edge_recombination_child<T>(first: &Vec<T>, second: &Vec<T>) -> Vec<T>
+    where T: Copy + PartialEq
+{
+    let length = first.len();
+    let mut second_as_indices = Vec::with_capacity(length);
+    for gene in second {
+        second_as_indices.push(position_in(first, *gene).expect("parents are not permutations of each other"));
+    }
+
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); length];
+    for i in 0..length {
+        let tours = [(i, (i + 1) % length, (i + length - 1) % length),
+                     (second_as_indices[i],
+                      second_as_indices[(i + 1) % length],
+                      second_as_indices[(i + length - 1) % length])];
+        for &(node, next, previous) in tours.iter() {
+            for &neighbour in [next, previous].iter() {
+                if neighbour != node && !neighbours[node].contains(&neighbour) {
+                    neighbours[node].push(neighbour);
                 }
-            } 
+            }
         }
-        
-        is_valid_permutation = true;
-        for i in 0..dad_genome.len() {
-            for j in (i+1)..dad_genome.len() {
-                if boy_genome[i] == boy_genome[j] || girl_genome[i] == girl_genome[j] {
-                    is_valid_permutation = false;
-                }
+    }
+
+    let mut rng = random::thread_rng();
+    let mut used = vec![false; length];
+    let mut child = Vec::with_capacity(length);
+    let mut current = rng.gen_range(0, length);
+
+    for _ in 0..length {
+        child.push(first[current]);
+        used[current] = true;
+        for list in neighbours.iter_mut() {
+            list.retain(|&node| node != current);
+        }
+
+        let candidates = neighbours[current].clone();
+        let next = if candidates.is_empty() {
+            let unused: Vec<usize> = (0..length).filter(|&node| !used[node]).collect();
+            if unused.is_empty() {
+                break;
             }
+            unused[rng.gen_range(0, unused.len())]
+        } else {
+            let fewest = candidates.iter().map(|&node| neighbours[node].len()).min().unwrap();
+            let ties: Vec<usize> = candidates.into_iter()
+                .filter(|&node| neighbours[node].len() == fewest)
+                .collect();
+            ties[rng.gen_range(0, ties.len())]
+        };
+        current = next;
+    }
+
+    child
+}
+
+pub fn position_based_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
+    where T: Copy + PartialEq
+{
+    let mut selected_positions = Vec::new();
+    for i in 0..dad_genome.len() {
+        if random::random::<f32>() < 0.5 {
+            selected_positions.push(i);
         }
     }
 
+    let boy_genome = position_based_child(dad_genome, mom_genome, &selected_positions);
+    let girl_genome = position_based_child(mom_genome, dad_genome, &selected_positions);
+
     (boy_genome, girl_genome)
 }
 
+fn position_based_child<T>(keeper: &Vec<T>, filler: &Vec<T>, positions: &Vec<usize>) -> Vec<T>
+    where T: Copy + PartialEq
+{
+    let kept: Vec<T> = positions.iter().map(|&i| keeper[i]).collect();
+    let mut remaining = filler.iter().filter(|gene| position_in(&kept, **gene).is_none());
+
+    let mut child = keeper.clone();
+    for i in 0..child.len() {
+        if positions.contains(&i) {
+            continue;
+        }
+        child[i] = *remaining.next().expect("parents are not permutations of each other");
+    }
+
+    child
+}
+
+fn position_in<T>(genes: &[T], gene: T) -> Option<usize>
+    where T: PartialEq
+{
+    genes.iter().position(|other| *other == gene)
+}
+
+// Returns a non-empty `start..end` slice range inside a genome of `length` >= 2.
+fn random_segment(length: usize) -> (usize, usize) {
+    let mut rng = random::thread_rng();
+    let a = rng.gen_range(0, length + 1);
+    let mut b = rng.gen_range(0, length);
+    if b >= a {
+        b += 1;
+    }
+    (cmp::min(a, b), cmp::max(a, b))
+}
+
 // TODO: Test the dank out of this function
 pub fn uniform_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>, Vec<T>)
     where T: Copy + PartialEq + Num + Div<Output = T> + Add<Output = T>
@@ -104,7 +279,7 @@ pub fn uniform_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<T>
     let mut girl_genome = mom_genome.clone();
 
     for i in 0..mom_genome.len() {
-        if rand::random::<f32>() > mix_ratio {
+        if random::random::<f32>() > mix_ratio {
             boy_genome[i] = mom_genome[i];
             girl_genome[i] = dad_genome[i]
         } 
@@ -125,7 +300,7 @@ pub fn uniform_average_crossover<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) ->
     for i in 0..mom_genome.len() {
         let average = (dad_genome[i] + mom_genome[i]) / (T::one() + T::one());
 
-        if rand::random::<f32>() > mix_ratio {
+        if random::random::<f32>() > mix_ratio {
             boy_genome[i] = average;
         } else {
             girl_genome[i] = average;
@@ -158,8 +333,8 @@ pub fn blend_crossover(dad_genome: &Vec<f32>, mom_genome: &Vec<f32>) -> (Vec<f32
         }
 
         let d = (dad_genome[i] - mom_genome[i]).abs();
-        boy_genome[i] = rand::thread_rng().gen_range(min - alpha * d, max + alpha * d);
-        girl_genome[i] = rand::thread_rng().gen_range(min - alpha * d, max + alpha * d);
+        boy_genome[i] = random::thread_rng().gen_range(min - alpha * d, max + alpha * d);
+        girl_genome[i] = random::thread_rng().gen_range(min - alpha * d, max + alpha * d);
     }
 
     (boy_genome, girl_genome)
@@ -175,7 +350,7 @@ pub fn uniform_crossover_3<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<
     let mut girl_genome = mom_genome.clone();
 
     for i in SimpleStepRange(0, mom_genome.len(), 3) {
-        if rand::random::<f32>() > mix_ratio {
+        if random::random::<f32>() > mix_ratio {
             boy_genome[i  ] = mom_genome[i  ];
             boy_genome[i+1] = mom_genome[i+1];
             boy_genome[i+2] = mom_genome[i+2];
@@ -188,3 +363,67 @@ pub fn uniform_crossover_3<T>(dad_genome: &Vec<T>, mom_genome: &Vec<T>) -> (Vec<
 
     (boy_genome, girl_genome)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genetic::helpers::is_permutation;
+
+    fn random_permutation(size: usize) -> Vec<i32> {
+        let mut genome: Vec<i32> = (0..size as i32).collect();
+        random::thread_rng().shuffle(&mut genome);
+        genome
+    }
+
+    fn assert_emits_permutations(crossover: fn(&Vec<i32>, &Vec<i32>) -> (Vec<i32>, Vec<i32>)) {
+        for _ in 0..500 {
+            let size = random::thread_rng().gen_range(1, 40);
+            let dad = random_permutation(size);
+            let mom = random_permutation(size);
+
+            let (boy, girl) = crossover(&dad, &mom);
+            assert!(is_permutation(&boy), "{:?} x {:?} gave {:?}", dad, mom, boy);
+            assert!(is_permutation(&girl), "{:?} x {:?} gave {:?}", dad, mom, girl);
+        }
+    }
+
+    #[test]
+    fn partially_matched_crossover_emits_permutations() {
+        assert_emits_permutations(partially_matched_crossover);
+    }
+
+    #[test]
+    fn order_crossover_emits_permutations() {
+        assert_emits_permutations(order_crossover);
+    }
+
+    #[test]
+    fn cycle_crossover_emits_permutations() {
+        assert_emits_permutations(cycle_crossover);
+    }
+
+    #[test]
+    fn edge_recombination_crossover_emits_permutations() {
+        assert_emits_permutations(edge_recombination_crossover);
+    }
+
+    #[test]
+    fn position_based_crossover_emits_permutations() {
+        assert_emits_permutations(position_based_crossover);
+    }
+
+    #[test]
+    fn cycle_crossover_keeps_every_gene_in_a_parent_position() {
+        for _ in 0..200 {
+            let size = random::thread_rng().gen_range(1, 40);
+            let dad = random_permutation(size);
+            let mom = random_permutation(size);
+
+            let (boy, girl) = cycle_crossover(&dad, &mom);
+            for i in 0..size {
+                assert!(boy[i] == dad[i] || boy[i] == mom[i]);
+                assert!(girl[i] == dad[i] || girl[i] == mom[i]);
+            }
+        }
+    }
+}
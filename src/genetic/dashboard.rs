@@ -0,0 +1,154 @@
+extern crate num;
+use self::num::Num;
+
+use std::f32;
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use genetic::observer::{Observer, Flow};
+use genetic::population::Population;
+
+const SPARK: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Redraws the whole terminal with ANSI escapes while a run goes on: progress,
+// rate and ETA, best and average fitness, convergence and diversity
+// sparklines and the best genome. Redraws are throttled to `refresh`, so
+// runs of many cheap generations do not spend their time drawing.
+pub struct Dashboard<T> {
+    pub total_generations: usize,
+    pub refresh: Duration,
+    // Sparkline width in characters.
+    pub width: usize,
+    // Most lines of the best genome shown.
+    pub genome_lines: usize,
+    render: Option<Box<Fn(&Vec<T>) -> String>>,
+    started: Instant,
+    last_drawn: Option<Instant>,
+}
+
+impl<T> Dashboard<T>
+    where T: Copy + PartialOrd + Num + Debug
+{
+    pub fn new(total_generations: usize) -> Dashboard<T> {
+        Dashboard {
+            total_generations: total_generations,
+            refresh: Duration::from_millis(100),
+            width: 60,
+            genome_lines: 12,
+            render: None,
+            started: Instant::now(),
+            last_drawn: None,
+        }
+    }
+
+    // Shows the best genome the way the problem draws it, instead of its Debug form.
+    pub fn with_renderer<R>(mut self, render: R) -> Dashboard<T>
+        where R: Fn(&Vec<T>) -> String + 'static
+    {
+        self.render = Some(Box::new(render));
+        self
+    }
+
+    fn draw(&mut self, population: &Population<T>, generations_done: usize) {
+        self.last_drawn = Some(Instant::now());
+        let elapsed = seconds(self.started.elapsed());
+        let rate = if elapsed > 0.0 { generations_done as f64 / elapsed } else { 0.0 };
+        let remaining = self.total_generations.saturating_sub(generations_done);
+        let eta = if rate > 0.0 { format_duration(remaining as f64 / rate) } else { "-".to_string() };
+
+        // Clear the screen and move home
+        let mut screen = "\x1b[H\x1b[2J".to_string();
+        screen.push_str(&format!("\x1b[1mgeneration {}/{}\x1b[0m  {:.1} gen/s  elapsed {}  ETA {}  evaluations {}\n\n",
+                                 generations_done, self.total_generations, rate,
+                                 format_duration(elapsed), eta, population.evaluations));
+
+        let average = population.average_fitness_in_generation.last().cloned().unwrap_or(0.0);
+        let diversity = population.diversity_in_generation.last().cloned().unwrap_or(0.0);
+        screen.push_str(&format!("best {:.6}  average {:.6}  diversity {:.4}\n\n",
+                                 population.best_fitness(), average, diversity));
+
+        screen.push_str(&format!("best      {}\n", sparkline(&population.best_fitness_in_generation, self.width)));
+        screen.push_str(&format!("average   {}\n", sparkline(&population.average_fitness_in_generation, self.width)));
+        screen.push_str(&format!("diversity {}\n\n", sparkline(&population.diversity_in_generation, self.width)));
+
+        if let Some(best_individual) = population.best_individual_in_generation.last() {
+            let picture = match self.render {
+                Some(ref render) => render(&best_individual.genome),
+                None => format!("{:?}", best_individual.genome),
+            };
+            screen.push_str("best genome:\n");
+            for line in picture.lines().take(self.genome_lines) {
+                screen.push_str(line);
+                screen.push('\n');
+            }
+        }
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        let _ = stdout.write_all(screen.as_bytes());
+        let _ = stdout.flush();
+    }
+}
+
+impl<T> Observer<T> for Dashboard<T>
+    where T: Copy + PartialOrd + Num + Debug
+{
+    fn on_generation_start(&mut self, _population: &Population<T>, generation: usize) {
+        if generation == 0 {
+            self.started = Instant::now();
+            // Hide the cursor while redrawing
+            print!("\x1b[?25l");
+        }
+    }
+
+    fn on_generation_end(&mut self, population: &Population<T>, generation: usize) -> Flow {
+        let due = self.last_drawn.map_or(true, |last_drawn| last_drawn.elapsed() >= self.refresh);
+        if due {
+            self.draw(population, generation + 1);
+        }
+        Flow::Continue
+    }
+
+    fn on_terminate(&mut self, population: &Population<T>) {
+        let generations_done = population.best_fitness_in_generation.len().saturating_sub(1);
+        self.draw(population, generations_done);
+        println!("\x1b[?25h");
+    }
+}
+
+// One block character per bucket of `values`, scaled between their minimum
+// and maximum. Each bucket shows its last value.
+pub fn sparkline(values: &[f32], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+    let buckets = width.min(values.len());
+    let samples: Vec<f32> = (0..buckets)
+        .map(|bucket| values[((bucket + 1) * values.len() / buckets) - 1])
+        .collect();
+
+    let min = samples.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = samples.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    samples.iter()
+        .map(|&value| {
+            let level = if max > min { (value - min) / (max - min) } else { 0.5 };
+            SPARK[((level * (SPARK.len() - 1) as f32).round() as usize).min(SPARK.len() - 1)]
+        })
+        .collect()
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 * 1e-9
+}
+
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds >= 3600 {
+        format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60)
+    } else if seconds >= 60 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -0,0 +1,148 @@
+use std::cmp::Ordering;
+
+use genetic::helpers::Range;
+use genetic::population::Individual;
+
+// Population diversity measures. Distances come from the population's
+// `diversity_function`, which is normalized to [0, 1] for every distance in
+// `helpers`, so the means below can be compared across population sizes.
+
+// How `Population` measures the diversity it records every generation.
+//  - Pairwise: `mean_pairwise_distance` under the diversity function.
+//  - Hamming: `mean_hamming_distance`, the same value as Pairwise when the
+//    diversity function is `hamming_distance`, without the quadratic cost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiversityMeasure {
+    Pairwise,
+    Hamming,
+}
+
+// Snapshot of the cheap measures, see `Population::diversity_report`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiversityReport {
+    pub mean_pairwise_distance: f32,
+    // Mean Shannon entropy of the loci, in bits.
+    pub mean_entropy: f32,
+    pub unique_genotypes: usize,
+}
+
+// Mean of `distance` over all pairs of distinct individuals, 0 for fewer than
+// two. O(n² · L); prefer `mean_hamming_distance` when the distance is Hamming.
+pub fn mean_pairwise_distance<T>(individuals: &[Individual<T>],
+                                 distance: fn(&Vec<T>, &Vec<T>, &Range<T>) -> f32,
+                                 range: &Range<T>) -> f32 {
+    let size = individuals.len();
+    if size < 2 {
+        return 0.0;
+    }
+
+    let mut total_distance = 0.0;
+    for i in 0..size {
+        for j in i + 1..size {
+            total_distance += distance(&individuals[i].genome, &individuals[j].genome, range);
+        }
+    }
+    total_distance / (size * (size - 1) / 2) as f32
+}
+
+// Same value as `mean_pairwise_distance` with `hamming_distance`, for any gene
+// type, in O(n · L · a) for `a` alleles per locus instead of O(n² · L): a locus
+// where allele `i` appears `c_i` times differs in `(n² - Σ c_i²) / 2` pairs.
+pub fn mean_hamming_distance<T: Copy + PartialEq>(individuals: &[Individual<T>]) -> f32 {
+    let size = individuals.len();
+    if size < 2 {
+        return 0.0;
+    }
+    let genome_length = individuals[0].genome.len();
+    if genome_length == 0 {
+        return 0.0;
+    }
+
+    let mut differing_pairs = 0;
+    for counts in allele_counts(individuals) {
+        let same_pairs: usize = counts.iter().map(|&(_, count)| count * count).sum();
+        differing_pairs += (size * size - same_pairs) / 2;
+    }
+    differing_pairs as f32 / (size * (size - 1) / 2 * genome_length) as f32
+}
+
+// For every locus, each allele present there and how many individuals carry it,
+// in order of first appearance.
+pub fn allele_counts<T: Copy + PartialEq>(individuals: &[Individual<T>]) -> Vec<Vec<(T, usize)>> {
+    let genome_length = individuals.first().map_or(0, |individual| individual.genome.len());
+    let mut loci: Vec<Vec<(T, usize)>> = vec![Vec::new(); genome_length];
+
+    for individual in individuals {
+        for (locus, &gene) in loci.iter_mut().zip(individual.genome.iter()) {
+            match locus.iter().position(|&(allele, _)| allele == gene) {
+                Some(i) => locus[i].1 += 1,
+                None => locus.push((gene, 1)),
+            }
+        }
+    }
+    loci
+}
+
+// Allele frequency profile: `allele_counts` divided by the population size.
+pub fn allele_frequencies<T: Copy + PartialEq>(individuals: &[Individual<T>]) -> Vec<Vec<(T, f32)>> {
+    let size = individuals.len() as f32;
+    allele_counts(individuals).into_iter()
+        .map(|locus| locus.into_iter().map(|(allele, count)| (allele, count as f32 / size)).collect())
+        .collect()
+}
+
+// Shannon entropy of every locus, in bits: 0 when the population agrees on
+// the locus, log2(alleles) when they are evenly spread.
+pub fn locus_entropy<T: Copy + PartialEq>(individuals: &[Individual<T>]) -> Vec<f32> {
+    allele_frequencies(individuals).iter()
+        .map(|locus| locus.iter().map(|&(_, frequency)| -frequency * frequency.log2()).sum::<f32>().max(0.0))
+        .collect()
+}
+
+pub fn mean_entropy<T: Copy + PartialEq>(individuals: &[Individual<T>]) -> f32 {
+    let entropies = locus_entropy(individuals);
+    if entropies.is_empty() {
+        return 0.0;
+    }
+    entropies.iter().sum::<f32>() / entropies.len() as f32
+}
+
+// Mean gene of every locus.
+pub fn centroid(individuals: &[Individual<f32>]) -> Vec<f32> {
+    let genome_length = individuals.first().map_or(0, |individual| individual.genome.len());
+    let mut centroid = vec![0.0; genome_length];
+    for individual in individuals {
+        for (mean, gene) in centroid.iter_mut().zip(individual.genome.iter()) {
+            *mean += gene / individuals.len() as f32;
+        }
+    }
+    centroid
+}
+
+// Mean Euclidean distance to the centroid, divided by the range width so it
+// reads in the same units whatever the bounds.
+pub fn mean_centroid_distance(individuals: &[Individual<f32>], range: &Range<f32>) -> f32 {
+    if individuals.is_empty() {
+        return 0.0;
+    }
+    let centroid = centroid(individuals);
+    let width = (range.end - range.start).abs().max(f32::EPSILON);
+
+    let total_distance: f32 = individuals.iter()
+        .map(|individual| {
+            individual.genome.iter().zip(centroid.iter())
+                .map(|(gene, mean)| (gene - mean).powi(2))
+                .sum::<f32>()
+                .sqrt()
+        })
+        .sum();
+    total_distance / individuals.len() as f32 / width
+}
+
+// Number of distinct genomes, O(n log n · L).
+pub fn unique_genotypes<T: PartialOrd>(individuals: &[Individual<T>]) -> usize {
+    let mut genomes: Vec<&Vec<T>> = individuals.iter().map(|individual| &individual.genome).collect();
+    genomes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    genomes.dedup_by(|a, b| a == b);
+    genomes.len()
+}
@@ -0,0 +1,304 @@
+use std::f32;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc;
+use std::thread;
+
+extern crate num;
+use self::num::Num;
+
+use genetic::population::Population;
+use genetic::random;
+
+// Repeats a run `trials` times on `threads` worker threads. Trial `i` seeds
+// the generator with `seed + i` before building its population, so a trial is
+// reproducible on its own whatever thread happens to run it.
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    pub trials: usize,
+    pub generations: usize,
+    pub seed: usize,
+    pub threads: usize,
+    // Fitness a trial has to reach to count as a success.
+    pub target: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Trial {
+    pub index: usize,
+    pub seed: usize,
+    // Best fitness seen so far, and evaluations spent, after each generation.
+    pub best_so_far: Vec<f32>,
+    pub evaluations: Vec<usize>,
+    // Generation and evaluations at which the target was first reached.
+    pub hit: Option<(usize, usize)>,
+}
+
+impl Trial {
+    pub fn best_fitness(&self) -> f32 {
+        self.best_so_far.last().cloned().unwrap_or(0.0)
+    }
+
+    pub fn total_evaluations(&self) -> usize {
+        self.evaluations.last().cloned().unwrap_or(0)
+    }
+
+    // Evaluations spent until the target was hit, or in the whole run.
+    pub fn evaluations_spent(&self) -> usize {
+        self.hit.map_or(self.total_evaluations(), |(_, evaluations)| evaluations)
+    }
+}
+
+// Best-so-far fitness across trials, one entry per generation.
+#[derive(Debug, Clone)]
+pub struct Curves {
+    pub mean: Vec<f32>,
+    pub median: Vec<f32>,
+    pub lower_quartile: Vec<f32>,
+    pub upper_quartile: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub target: Option<f32>,
+    // Sorted by trial index.
+    pub trials: Vec<Trial>,
+    pub curves: Curves,
+}
+
+impl Experiment {
+    pub fn new(trials: usize, generations: usize) -> Experiment {
+        Experiment {
+            trials: trials,
+            generations: generations,
+            seed: 0,
+            threads: 4,
+            target: None,
+        }
+    }
+
+    pub fn run<T, F>(&self, build: F) -> Summary
+        where T: Copy + PartialOrd + Num + 'static,
+              F: Fn() -> Population<T> + Send + Sync + 'static
+    {
+        let build = Arc::new(build);
+        let next_trial = Arc::new(Mutex::new(0));
+        let (sender, receiver) = mpsc::channel();
+
+        let workers: Vec<_> = (0..self.threads.max(1).min(self.trials.max(1)))
+            .map(|_| {
+                let build = build.clone();
+                let next_trial = next_trial.clone();
+                let sender = sender.clone();
+                let experiment = self.clone();
+                thread::spawn(move || loop {
+                    let index = {
+                        let mut next_trial = next_trial.lock().unwrap();
+                        if *next_trial >= experiment.trials {
+                            break;
+                        }
+                        *next_trial += 1;
+                        *next_trial - 1
+                    };
+                    let trial = experiment.run_trial(index, &*build);
+                    if sender.send(trial).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+
+        let mut trials: Vec<Trial> = receiver.iter().collect();
+        for worker in workers {
+            worker.join().expect("experiment worker panicked");
+        }
+        trials.sort_by_key(|trial| trial.index);
+
+        let curves = curves(&trials);
+        Summary { target: self.target, trials: trials, curves: curves }
+    }
+
+    fn run_trial<T, F>(&self, index: usize, build: &F) -> Trial
+        where T: Copy + PartialOrd + Num,
+              F: Fn() -> Population<T>
+    {
+        let seed = self.seed + index;
+        random::seed(seed);
+        let mut population = build();
+        population.run(self.generations);
+
+        let mut best_so_far = Vec::with_capacity(population.best_fitness_in_generation.len());
+        let mut best = f32::NEG_INFINITY;
+        for &fitness in &population.best_fitness_in_generation {
+            best = best.max(fitness);
+            best_so_far.push(best);
+        }
+
+        let hit = self.target.and_then(|target| {
+            best_so_far.iter()
+                .position(|&fitness| reaches(fitness, target))
+                .map(|generation| (generation, population.evaluations_in_generation[generation]))
+        });
+
+        info!("trial={} seed={} best={} hit={:?} evaluations={}",
+              index, seed, best, hit, population.evaluations);
+        Trial {
+            index: index,
+            seed: seed,
+            best_so_far: best_so_far,
+            evaluations: population.evaluations_in_generation.clone(),
+            hit: hit,
+        }
+    }
+}
+
+// Same tolerance as `Problem::is_solved`.
+fn reaches(fitness: f32, target: f32) -> bool {
+    target - fitness <= 1e-6 * target.abs().max(1.0)
+}
+
+// Trials stopped early by an observer keep their last value for the rest.
+fn curves(trials: &[Trial]) -> Curves {
+    let generations = trials.iter().map(|trial| trial.best_so_far.len()).max().unwrap_or(0);
+    let mut curves = Curves {
+        mean: Vec::with_capacity(generations),
+        median: Vec::with_capacity(generations),
+        lower_quartile: Vec::with_capacity(generations),
+        upper_quartile: Vec::with_capacity(generations),
+    };
+
+    for generation in 0..generations {
+        let mut values: Vec<f32> = trials.iter()
+            .filter_map(|trial| trial.best_so_far.get(generation).or(trial.best_so_far.last()).cloned())
+            .collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+
+        curves.mean.push(values.iter().sum::<f32>() / values.len() as f32);
+        curves.median.push(quantile(&values, 0.5));
+        curves.lower_quartile.push(quantile(&values, 0.25));
+        curves.upper_quartile.push(quantile(&values, 0.75));
+    }
+    curves
+}
+
+// Linear interpolation between the closest ranks of already sorted values.
+pub fn quantile(sorted: &[f32], q: f32) -> f32 {
+    if sorted.is_empty() {
+        return f32::NAN;
+    }
+    let position = q.max(0.0).min(1.0) * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    // Equal bounds also keep infinite values from interpolating to NaN
+    if lower == upper || sorted[lower] == sorted[upper] {
+        return sorted[lower];
+    }
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
+
+impl Summary {
+    pub fn successes(&self) -> usize {
+        self.trials.iter().filter(|trial| trial.hit.is_some()).count()
+    }
+
+    // None without a target.
+    pub fn success_rate(&self) -> Option<f32> {
+        self.target.map(|_| self.successes() as f32 / self.trials.len().max(1) as f32)
+    }
+
+    // Expected running time: evaluations spent by all trials, each until it
+    // hit the target or ran out, per successful trial. None without successes.
+    pub fn expected_running_time(&self) -> Option<f32> {
+        let successes = self.successes();
+        if successes == 0 {
+            return None;
+        }
+        let spent: usize = self.trials.iter().map(|trial| trial.evaluations_spent()).sum();
+        Some(spent as f32 / successes as f32)
+    }
+
+    // Final best fitness of every trial, sorted.
+    pub fn final_fitnesses(&self) -> Vec<f32> {
+        let mut fitnesses: Vec<f32> = self.trials.iter().map(|trial| trial.best_fitness()).collect();
+        fitnesses.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+        fitnesses
+    }
+
+    // One line per trial: index, seed, final best, hit generation and
+    // evaluations (empty when missed) and total evaluations.
+    pub fn trials_csv(&self) -> String {
+        let mut csv = "trial,seed,best_fitness,hit_generation,hit_evaluations,evaluations\n".to_string();
+        for trial in &self.trials {
+            let (hit_generation, hit_evaluations) = match trial.hit {
+                Some((generation, evaluations)) => (generation.to_string(), evaluations.to_string()),
+                None => (String::new(), String::new()),
+            };
+            csv.push_str(&format!("{},{},{},{},{},{}\n",
+                                  trial.index,
+                                  trial.seed,
+                                  trial.best_fitness(),
+                                  hit_generation,
+                                  hit_evaluations,
+                                  trial.total_evaluations()));
+        }
+        csv
+    }
+
+    pub fn trial_csv(&self, index: usize) -> String {
+        let trial = &self.trials[index];
+        let mut csv = "generation,evaluations,best_so_far\n".to_string();
+        for generation in 0..trial.best_so_far.len() {
+            csv.push_str(&format!("{},{},{}\n", generation, trial.evaluations[generation], trial.best_so_far[generation]));
+        }
+        csv
+    }
+
+    pub fn curves_csv(&self) -> String {
+        let mut csv = "generation,mean,median,lower_quartile,upper_quartile\n".to_string();
+        for generation in 0..self.curves.mean.len() {
+            csv.push_str(&format!("{},{},{},{},{}\n",
+                                  generation,
+                                  self.curves.mean[generation],
+                                  self.curves.median[generation],
+                                  self.curves.lower_quartile[generation],
+                                  self.curves.upper_quartile[generation]));
+        }
+        csv
+    }
+
+    pub fn report(&self) -> String {
+        let fitnesses = self.final_fitnesses();
+        let mut report = format!("trials: {}\n", self.trials.len());
+        report.push_str(&format!("final best fitness: mean {:.6}, median {:.6}, quartiles {:.6} .. {:.6}\n",
+                                 fitnesses.iter().sum::<f32>() / fitnesses.len().max(1) as f32,
+                                 quantile(&fitnesses, 0.5),
+                                 quantile(&fitnesses, 0.25),
+                                 quantile(&fitnesses, 0.75)));
+        if let (Some(target), Some(success_rate)) = (self.target, self.success_rate()) {
+            report.push_str(&format!("target {}: {} of {} trials ({:.1}%)\n",
+                                     target, self.successes(), self.trials.len(), 100.0 * success_rate));
+            match self.expected_running_time() {
+                Some(ert) => report.push_str(&format!("expected running time: {:.1} evaluations\n", ert)),
+                None => report.push_str("expected running time: unknown, the target was never reached\n"),
+            }
+        }
+        report
+    }
+
+    // trials.csv, curves.csv and summary.txt, plus trial-N.csv with the
+    // best-so-far curve of every trial.
+    pub fn write_to(&self, directory: &Path) -> io::Result<()> {
+        fs::create_dir_all(directory)?;
+        File::create(directory.join("trials.csv"))?.write_all(self.trials_csv().as_bytes())?;
+        File::create(directory.join("curves.csv"))?.write_all(self.curves_csv().as_bytes())?;
+        File::create(directory.join("summary.txt"))?.write_all(self.report().as_bytes())?;
+        for trial in &self.trials {
+            File::create(directory.join(format!("trial-{}.csv", trial.index)))?
+                .write_all(self.trial_csv(trial.index).as_bytes())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::f32;
+
+// Where an individual came from, carried by `Individual::lineage` and
+// recorded while the population tracks its genealogy, see
+// `Population::enable_genealogy`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lineage {
+    // Births of the parents; empty for the initial population.
+    pub parents: Vec<usize>,
+    // Generation the individual was bred in.
+    pub generation: usize,
+    // Operators that changed the genome, in the order they were applied.
+    // Offspring that are plain copies of a parent have none.
+    pub operators: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub id: usize,
+    pub lineage: Lineage,
+    // First fitness evaluated for the individual, if it ever was.
+    pub fitness: Option<f32>,
+}
+
+// How often an operator was involved in breeding an individual and how often
+// that individual beat the best of its parents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorImprovements {
+    pub operator: &'static str,
+    pub applications: usize,
+    pub improvements: usize,
+}
+
+// Every individual bred while tracking, keyed by birth.
+#[derive(Debug, Clone)]
+pub struct Genealogy {
+    records: BTreeMap<usize, Record>,
+}
+
+impl Genealogy {
+    pub fn new() -> Genealogy {
+        Genealogy { records: BTreeMap::new() }
+    }
+
+    pub fn record(&mut self, id: usize, lineage: &Lineage) {
+        self.records.insert(id, Record { id: id, lineage: lineage.clone(), fitness: None });
+    }
+
+    pub fn set_fitness(&mut self, id: usize, fitness: f32) {
+        if let Some(record) = self.records.get_mut(&id) {
+            if record.fitness.is_none() {
+                record.fitness = Some(fitness);
+            }
+        }
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Record> {
+        self.records.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn records(&self) -> Vec<&Record> {
+        self.records.values().collect()
+    }
+
+    // The individual and all its recorded ancestors, as a genealogy of its own
+    // that can be exported.
+    pub fn ancestry(&self, id: usize) -> Genealogy {
+        let mut ancestry = Genealogy::new();
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            if ancestry.records.contains_key(&current) {
+                continue;
+            }
+            if let Some(record) = self.records.get(&current) {
+                pending.extend(record.lineage.parents.iter().cloned());
+                ancestry.records.insert(current, record.clone());
+            }
+        }
+        ancestry
+    }
+
+    // Path from the individual back to the initial population, following the
+    // fittest parent at every step. Starts with the individual itself.
+    pub fn line_of_descent(&self, id: usize) -> Vec<&Record> {
+        let mut line = Vec::new();
+        let mut current = self.records.get(&id);
+        while let Some(record) = current {
+            line.push(record);
+            current = record.lineage.parents.iter()
+                .filter_map(|parent| self.records.get(parent))
+                .fold(None, |fittest: Option<&Record>, parent| match fittest {
+                    Some(fittest) if fittest.fitness.unwrap_or(f32::NEG_INFINITY) >=
+                                     parent.fitness.unwrap_or(f32::NEG_INFINITY) => Some(fittest),
+                    _ => Some(parent),
+                });
+        }
+        line
+    }
+
+    // Per operator, over every evaluated individual whose parents were
+    // evaluated too. Sorted by improvements, most first.
+    pub fn improvements_by_operator(&self) -> Vec<OperatorImprovements> {
+        let mut tally: HashMap<&'static str, (usize, usize)> = HashMap::new();
+        for record in self.records.values() {
+            let fitness = match record.fitness {
+                Some(fitness) => fitness,
+                None => continue,
+            };
+            let parent_fitnesses: Vec<f32> = record.lineage.parents.iter()
+                .filter_map(|parent| self.records.get(parent).and_then(|parent| parent.fitness))
+                .collect();
+            if parent_fitnesses.is_empty() {
+                continue;
+            }
+            let best_parent = parent_fitnesses.iter().fold(f32::NEG_INFINITY, |best, &fitness| best.max(fitness));
+
+            let operators: BTreeSet<&'static str> = record.lineage.operators.iter().cloned().collect();
+            for operator in operators {
+                let entry = tally.entry(operator).or_insert((0, 0));
+                entry.0 += 1;
+                if fitness > best_parent {
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        let mut improvements: Vec<OperatorImprovements> = tally.into_iter()
+            .map(|(operator, (applications, improvements))| OperatorImprovements {
+                operator: operator,
+                applications: applications,
+                improvements: improvements,
+            })
+            .collect();
+        improvements.sort_by(|a, b| b.improvements.cmp(&a.improvements).then(a.operator.cmp(b.operator)));
+        improvements
+    }
+
+    // Graphviz digraph, parents pointing at children. Edges are labelled with
+    // the operators that produced the child.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph genealogy {\n    rankdir=TB;\n    node [shape=box];\n");
+        for record in self.records.values() {
+            let fitness = record.fitness.map_or("?".to_string(), |fitness| format!("{:.4}", fitness));
+            dot.push_str(&format!("    n{} [label=\"#{}\\ngeneration {}\\nfitness {}\"];\n",
+                                  record.id, record.id, record.lineage.generation, fitness));
+        }
+        for record in self.records.values() {
+            let label = record.lineage.operators.join("+");
+            for parent in &record.lineage.parents {
+                if self.records.contains_key(parent) {
+                    dot.push_str(&format!("    n{} -> n{} [label=\"{}\"];\n", parent, record.id, label));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // JSON array with one object per individual, ordered by birth.
+    pub fn to_json(&self) -> String {
+        let objects: Vec<String> = self.records.values()
+            .map(|record| {
+                let parents: Vec<String> = record.lineage.parents.iter().map(|parent| parent.to_string()).collect();
+                let operators: Vec<String> = record.lineage.operators.iter()
+                    .map(|operator| format!("\"{}\"", operator))
+                    .collect();
+                let fitness = match record.fitness {
+                    Some(fitness) if fitness.is_finite() => fitness.to_string(),
+                    _ => "null".to_string(),
+                };
+                format!("  {{\"id\": {}, \"parents\": [{}], \"generation\": {}, \"operators\": [{}], \"fitness\": {}}}",
+                        record.id, parents.join(", "), record.lineage.generation, operators.join(", "), fitness)
+            })
+            .collect();
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
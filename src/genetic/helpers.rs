@@ -24,6 +24,19 @@ pub fn binary_vector_to_decimal(binary_vector: &Vec<u8>) -> i32 {
     decimal
 }
 
+// True when the genome holds every value of 0..genome.len() exactly once,
+// which is the encoding produced by `Individual::new_ordered`.
+pub fn is_permutation(genome: &Vec<i32>) -> bool {
+    let mut seen = vec![false; genome.len()];
+    for gene in genome {
+        if *gene < 0 || *gene as usize >= genome.len() || seen[*gene as usize] {
+            return false;
+        }
+        seen[*gene as usize] = true;
+    }
+    true
+}
+
 pub fn hamming_distance(genome_one: &Vec<u8>, genome_two: &Vec<u8>, range: &Range<u8>) -> f32 {
     let mut total_distance = 0;
     for i in 0..genome_one.len() {
@@ -54,8 +67,10 @@ pub fn euclidean_distance_float(genome_one: &Vec<f32>, genome_two: &Vec<f32>, ra
         total_distance += (genome_one[i] - genome_two[i]).powf(2.0);
     }
 
-    let max_distance = genome_one.len() as f32 * ((range.end - range.start).powf(2.0) as f32).sqrt();
-    (total_distance).sqrt() / max_distance
+    // Delta and Gaussian mutation can move genes out of the range, so the
+    // distance is clamped to stay normalized.
+    let max_distance = (genome_one.len() as f32).sqrt() * (range.end - range.start).abs();
+    ((total_distance).sqrt() / max_distance).max(0.0).min(1.0)
 }
 
 
@@ -77,3 +92,16 @@ impl Iterator for SimpleStepRange {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn euclidean_distance_float_stays_normalized() {
+        let range = Range::new(0.0, 1.0);
+        assert_eq!(euclidean_distance_float(&vec![0.0, 0.0], &vec![1.0, 1.0], &range), 1.0);
+        assert_eq!(euclidean_distance_float(&vec![-3.0, 0.0], &vec![4.0, 1.0], &range), 1.0);
+        assert_eq!(euclidean_distance_float(&vec![0.5], &vec![0.5], &Range::new(1.0, 1.0)), 0.0);
+    }
+}
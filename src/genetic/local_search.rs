@@ -0,0 +1,333 @@
+extern crate rand;
+use self::rand::Rng;
+
+use genetic::helpers::Range;
+use genetic::random;
+
+// Local improvement applied to offspring by memetic runs, see
+// `Population::set_local_search`.
+pub trait LocalSearch<T> {
+    // Improves `genome` in place spending at most `budget` calls to `fitness`,
+    // and returns the fitness of the genome left behind. A zero budget leaves
+    // the genome alone and returns None.
+    fn improve(&self, genome: &mut Vec<T>, fitness: &Fn(&Vec<T>) -> f32, budget: usize, range: &Range<T>)
+        -> Option<f32>;
+}
+
+// What happens to an improved offspring.
+//  - Lamarckian: the improved genome replaces the offspring's genome.
+//  - Baldwinian: the genome is left alone, only its fitness is the improved one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WriteBack {
+    Lamarckian,
+    Baldwinian,
+}
+
+// First-improvement bit-flip hill climbing for binary genomes: visits the loci
+// in random order and keeps every flip that raises fitness.
+pub struct BitFlipHillClimbing;
+
+impl LocalSearch<u8> for BitFlipHillClimbing {
+    fn improve(&self, genome: &mut Vec<u8>, fitness: &Fn(&Vec<u8>) -> f32, budget: usize, range: &Range<u8>)
+        -> Option<f32>
+    {
+        if budget == 0 {
+            return None;
+        }
+        let mut current_fitness = fitness(genome);
+        let mut evaluations = 1;
+
+        let mut improved = true;
+        while improved && evaluations < budget {
+            improved = false;
+            let mut loci: Vec<usize> = (0..genome.len()).collect();
+            random::thread_rng().shuffle(&mut loci);
+
+            for i in loci {
+                if evaluations >= budget {
+                    break;
+                }
+                genome[i] ^= 1;
+                let candidate_fitness = fitness(genome);
+                evaluations += 1;
+
+                if candidate_fitness > current_fitness {
+                    current_fitness = candidate_fitness;
+                    improved = true;
+                } else {
+                    genome[i] ^= 1;
+                }
+            }
+        }
+
+        Some(current_fitness)
+    }
+}
+
+// Random swap descent for permutations: tries swapping two random positions
+// and keeps the swap when it improves fitness.
+pub struct SwapDescent;
+
+impl LocalSearch<i32> for SwapDescent {
+    fn improve(&self, genome: &mut Vec<i32>, fitness: &Fn(&Vec<i32>) -> f32, budget: usize, range: &Range<i32>)
+        -> Option<f32>
+    {
+        if budget == 0 {
+            return None;
+        }
+        let mut current_fitness = fitness(genome);
+        if genome.len() < 2 {
+            return Some(current_fitness);
+        }
+
+        let mut rng = random::thread_rng();
+        for _ in 1..budget {
+            let i = rng.gen_range(0, genome.len());
+            let j = rng.gen_range(0, genome.len());
+            genome.swap(i, j);
+            let candidate_fitness = fitness(genome);
+
+            if candidate_fitness > current_fitness {
+                current_fitness = candidate_fitness;
+            } else {
+                genome.swap(i, j);
+            }
+        }
+
+        Some(current_fitness)
+    }
+}
+
+// 2-opt descent for tours: reverses the stretch between two random positions
+// and keeps the reversal when it improves fitness.
+pub struct TwoOptDescent;
+
+impl LocalSearch<i32> for TwoOptDescent {
+    fn improve(&self, genome: &mut Vec<i32>, fitness: &Fn(&Vec<i32>) -> f32, budget: usize, range: &Range<i32>)
+        -> Option<f32>
+    {
+        if budget == 0 {
+            return None;
+        }
+        let mut current_fitness = fitness(genome);
+        if genome.len() < 3 {
+            return Some(current_fitness);
+        }
+
+        let mut rng = random::thread_rng();
+        for _ in 1..budget {
+            let a = rng.gen_range(0, genome.len());
+            let b = rng.gen_range(0, genome.len());
+            let (start, end) = if a < b { (a, b) } else { (b, a) };
+            genome[start..end + 1].reverse();
+            let candidate_fitness = fitness(genome);
+
+            if candidate_fitness > current_fitness {
+                current_fitness = candidate_fitness;
+            } else {
+                genome[start..end + 1].reverse();
+            }
+        }
+
+        Some(current_fitness)
+    }
+}
+
+// Coordinate descent for real genomes: steps each coordinate up and down by
+// `step` (a fraction of the range width), halving the step after a full sweep
+// without improvement.
+pub struct CoordinateDescent {
+    pub step: f32,
+}
+
+impl LocalSearch<f32> for CoordinateDescent {
+    fn improve(&self, genome: &mut Vec<f32>, fitness: &Fn(&Vec<f32>) -> f32, budget: usize, range: &Range<f32>)
+        -> Option<f32>
+    {
+        if budget == 0 {
+            return None;
+        }
+        let mut current_fitness = fitness(genome);
+        let mut evaluations = 1;
+        let mut step = self.step * (range.end - range.start);
+
+        while evaluations < budget && step > 0.0 {
+            let mut improved = false;
+            for i in 0..genome.len() {
+                for &direction in [1.0, -1.0].iter() {
+                    if evaluations >= budget {
+                        break;
+                    }
+                    let old_value = genome[i];
+                    genome[i] = clamp(old_value + direction * step, range);
+                    let candidate_fitness = fitness(genome);
+                    evaluations += 1;
+
+                    if candidate_fitness > current_fitness {
+                        current_fitness = candidate_fitness;
+                        improved = true;
+                        break;
+                    }
+                    genome[i] = old_value;
+                }
+            }
+            if !improved {
+                step /= 2.0;
+            }
+        }
+
+        Some(current_fitness)
+    }
+}
+
+// Nelder-Mead simplex search for real genomes, maximizing fitness. The initial
+// simplex spreads `step` (a fraction of the range width) along every axis and
+// already costs `genome.len() + 1` evaluations of the budget.
+pub struct NelderMead {
+    pub step: f32,
+}
+
+impl LocalSearch<f32> for NelderMead {
+    fn improve(&self, genome: &mut Vec<f32>, fitness: &Fn(&Vec<f32>) -> f32, budget: usize, range: &Range<f32>)
+        -> Option<f32>
+    {
+        if budget == 0 {
+            return None;
+        }
+        let (reflection, expansion, contraction, shrink) = (1.0, 2.0, 0.5, 0.5);
+        let dimensions = genome.len();
+        let step = self.step * (range.end - range.start);
+        if dimensions == 0 {
+            return Some(fitness(genome));
+        }
+
+        let mut simplex: Vec<(Vec<f32>, f32)> = vec![(genome.clone(), fitness(genome))];
+        let mut evaluations = 1;
+        for i in 0..dimensions {
+            if evaluations >= budget {
+                break;
+            }
+            let mut vertex = genome.clone();
+            vertex[i] = if vertex[i] + step <= range.end { vertex[i] + step } else { vertex[i] - step };
+            vertex[i] = clamp(vertex[i], range);
+            let vertex_fitness = fitness(&vertex);
+            simplex.push((vertex, vertex_fitness));
+            evaluations += 1;
+        }
+
+        while simplex.len() == dimensions + 1 && evaluations < budget {
+            simplex.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(::std::cmp::Ordering::Equal));
+            let worst = dimensions;
+
+            let mut centroid = vec![0.0; dimensions];
+            for &(ref vertex, _) in &simplex[..worst] {
+                for i in 0..dimensions {
+                    centroid[i] += vertex[i] / dimensions as f32;
+                }
+            }
+            let towards = |from: &Vec<f32>, factor: f32| -> Vec<f32> {
+                (0..dimensions).map(|i| clamp(centroid[i] + factor * (from[i] - centroid[i]), range)).collect()
+            };
+
+            let reflected = towards(&simplex[worst].0, -reflection);
+            let reflected_fitness = fitness(&reflected);
+            evaluations += 1;
+
+            if reflected_fitness > simplex[0].1 && evaluations < budget {
+                let expanded = towards(&reflected, expansion);
+                let expanded_fitness = fitness(&expanded);
+                evaluations += 1;
+                simplex[worst] = if expanded_fitness > reflected_fitness {
+                    (expanded, expanded_fitness)
+                } else {
+                    (reflected, reflected_fitness)
+                };
+            } else if reflected_fitness > simplex[worst - 1].1 {
+                simplex[worst] = (reflected, reflected_fitness);
+            } else if evaluations < budget {
+                let contracted = towards(&simplex[worst].0, contraction);
+                let contracted_fitness = fitness(&contracted);
+                evaluations += 1;
+
+                if contracted_fitness > simplex[worst].1 {
+                    simplex[worst] = (contracted, contracted_fitness);
+                } else {
+                    let best = simplex[0].0.clone();
+                    for vertex_index in 1..simplex.len() {
+                        if evaluations >= budget {
+                            break;
+                        }
+                        let shrunk: Vec<f32> = (0..dimensions)
+                            .map(|i| best[i] + shrink * (simplex[vertex_index].0[i] - best[i]))
+                            .collect();
+                        let shrunk_fitness = fitness(&shrunk);
+                        evaluations += 1;
+                        simplex[vertex_index] = (shrunk, shrunk_fitness);
+                    }
+                }
+            }
+        }
+
+        let mut best_index = 0;
+        for i in 1..simplex.len() {
+            if simplex[i].1 > simplex[best_index].1 {
+                best_index = i;
+            }
+        }
+        genome.clone_from(&simplex[best_index].0);
+        Some(simplex[best_index].1)
+    }
+}
+
+fn clamp(value: f32, range: &Range<f32>) -> f32 {
+    value.max(range.start).min(range.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // Runs `search` and returns its result with the evaluations it spent.
+    fn spend<T, L: LocalSearch<T>>(search: L, mut genome: Vec<T>, budget: usize, range: Range<T>,
+                                   fitness: fn(&Vec<T>) -> f32) -> (Option<f32>, usize) {
+        let evaluations = Cell::new(0);
+        let counted = |genome: &Vec<T>| { evaluations.set(evaluations.get() + 1); fitness(genome) };
+        let result = search.improve(&mut genome, &counted, budget, &range);
+        (result, evaluations.get())
+    }
+
+    fn ones(genome: &Vec<u8>) -> f32 {
+        genome.iter().map(|&gene| gene as f32).sum()
+    }
+
+    fn sorted(genome: &Vec<i32>) -> f32 {
+        genome.windows(2).filter(|pair| pair[0] < pair[1]).count() as f32
+    }
+
+    fn sphere(genome: &Vec<f32>) -> f32 {
+        -genome.iter().map(|gene| gene * gene).sum::<f32>()
+    }
+
+    #[test]
+    fn zero_budget_costs_nothing() {
+        assert_eq!(spend(BitFlipHillClimbing, vec![0; 8], 0, Range::new(0, 1), ones), (None, 0));
+        assert_eq!(spend(SwapDescent, vec![3, 1, 2, 0], 0, Range::new(0, 3), sorted), (None, 0));
+        assert_eq!(spend(TwoOptDescent, vec![3, 1, 2, 0], 0, Range::new(0, 3), sorted), (None, 0));
+        let range = Range::new(-5.0, 5.0);
+        assert_eq!(spend(CoordinateDescent { step: 0.1 }, vec![1.0; 3], 0, range, sphere), (None, 0));
+        assert_eq!(spend(NelderMead { step: 0.1 }, vec![1.0; 3], 0, range, sphere), (None, 0));
+    }
+
+    #[test]
+    fn searches_stay_within_budget() {
+        for budget in 1..12 {
+            assert!(spend(BitFlipHillClimbing, vec![0; 8], budget, Range::new(0, 1), ones).1 <= budget);
+            assert!(spend(SwapDescent, vec![3, 1, 2, 0], budget, Range::new(0, 3), sorted).1 <= budget);
+            assert!(spend(TwoOptDescent, vec![3, 1, 2, 0], budget, Range::new(0, 3), sorted).1 <= budget);
+            let range = Range::new(-5.0, 5.0);
+            assert!(spend(CoordinateDescent { step: 0.1 }, vec![1.0; 3], budget, range, sphere).1 <= budget);
+            assert!(spend(NelderMead { step: 0.1 }, vec![1.0; 3], budget, range, sphere).1 <= budget);
+        }
+    }
+}
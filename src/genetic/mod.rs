@@ -1,5 +1,20 @@
 pub mod population;
 pub mod fitness;
 pub mod helpers;
+pub mod random;
 pub mod mutation;
 pub mod crossover;
+pub mod local_search;
+pub mod replacement;
+pub mod scaling;
+pub mod diversity;
+pub mod genealogy;
+pub mod credit;
+pub mod portfolio;
+pub mod experiment;
+pub mod sweep;
+pub mod statistics;
+pub mod observer;
+pub mod dashboard;
+pub mod plot;
+pub mod problems;
@@ -4,6 +4,7 @@ use self::rand::Rng;
 use std::f32;
 
 use genetic::helpers::Range;
+use genetic::random;
 
 pub trait Mutation<T> {
     fn mutate(&mut self, f: &Fn(&mut Vec<T>, f32, &Range<T>), probability: f32, range: &Range<T>);
@@ -21,7 +22,7 @@ impl<T> Mutation<T> for Vec<T> {
 
 pub fn bit_flip(genome: &mut Vec<u8>, probability: f32, range: &Range<u8>) {
     for gene in genome.iter_mut() {
-        if rand::random::<f32>() > probability {
+        if random::random::<f32>() > probability {
             continue;
         }
 
@@ -31,13 +32,13 @@ pub fn bit_flip(genome: &mut Vec<u8>, probability: f32, range: &Range<u8>) {
 
 pub fn delta_mutation(genome: &mut Vec<f32>, probability: f32, range: &Range<f32>) {
     for gene in genome.iter_mut() {
-        if rand::random::<f32>() > probability {
+        if random::random::<f32>() > probability {
             continue;
         }
 
         let delta_factor = (range.start - range.end) / 100.0;
         
-        let delta = rand::random::<f32>() * delta_factor;
+        let delta = random::random::<f32>() * delta_factor;
         let new_value = *gene + delta;
         *gene = new_value;
     }
@@ -45,30 +46,30 @@ pub fn delta_mutation(genome: &mut Vec<f32>, probability: f32, range: &Range<f32
 
 pub fn random_int(genome: &mut Vec<i32>, probability: f32, range: &Range<i32>) {
     for gene in genome.iter_mut() {
-        if rand::random::<f32>() > probability {
+        if random::random::<f32>() > probability {
             continue;
         }
         
-        *gene = rand::thread_rng().gen_range(range.start, range.end + 1);;
+        *gene = random::thread_rng().gen_range(range.start, range.end + 1);;
     }
 }
 
 pub fn random_real(genome: &mut Vec<f32>, probability: f32, range: &Range<f32>) {
     for gene in genome.iter_mut() {
-        if rand::random::<f32>() > probability {
+        if random::random::<f32>() > probability {
             continue;
         }
-        *gene = rand::thread_rng().gen_range(range.start, range.end + 1.0);;
+        *gene = random::thread_rng().gen_range(range.start, range.end);
     }
 }
 
 pub fn swap_position(genome: &mut Vec<i32>, probability: f32, range: &Range<i32>) {
     for i in 0..genome.len() {
-        if rand::random::<f32>() > probability {
+        if random::random::<f32>() > probability {
             continue;
         }
 
-        let pair_index = rand::thread_rng().gen_range(0, genome.len());
+        let pair_index = random::thread_rng().gen_range(0, genome.len());
         
         let old_value = genome[i];
         genome[i] = genome[pair_index];
@@ -76,13 +77,76 @@ pub fn swap_position(genome: &mut Vec<i32>, probability: f32, range: &Range<i32>
     }
 }
 
+pub fn inversion_mutation(genome: &mut Vec<i32>, probability: f32, _range: &Range<i32>) {
+    for _ in 0..genome.len() {
+        if random::random::<f32>() > probability {
+            continue;
+        }
+
+        let (start_index, end_index) = random_cut_points(genome.len());
+        genome[start_index..end_index + 1].reverse();
+    }
+}
+
+pub fn scramble_mutation(genome: &mut Vec<i32>, probability: f32, _range: &Range<i32>) {
+    for _ in 0..genome.len() {
+        if random::random::<f32>() > probability {
+            continue;
+        }
+
+        let (start_index, end_index) = random_cut_points(genome.len());
+        random::thread_rng().shuffle(&mut genome[start_index..end_index + 1]);
+    }
+}
+
+pub fn insertion_mutation(genome: &mut Vec<i32>, probability: f32, _range: &Range<i32>) {
+    for _ in 0..genome.len() {
+        if random::random::<f32>() > probability {
+            continue;
+        }
+
+        let from_index = random::thread_rng().gen_range(0, genome.len());
+        let to_index = random::thread_rng().gen_range(0, genome.len());
+        let gene = genome.remove(from_index);
+        genome.insert(to_index, gene);
+    }
+}
+
+// Treats the genome as a closed tour: removes two edges and reconnects them the
+// other way around, so the reversed stretch may wrap past the end of the vector.
+pub fn two_opt_mutation(genome: &mut Vec<i32>, probability: f32, _range: &Range<i32>) {
+    let length = genome.len();
+    for _ in 0..length {
+        if random::random::<f32>() > probability {
+            continue;
+        }
+
+        let (first_edge, second_edge) = random_cut_points(length);
+        let offset = random::thread_rng().gen_range(0, length);
+        let mut left = first_edge + 1;
+        let mut right = second_edge;
+        while left < right {
+            genome.swap((left + offset) % length, (right + offset) % length);
+            left += 1;
+            right -= 1;
+        }
+    }
+}
+
+// Two inclusive indices with start <= end, both within a genome of `length` > 0.
+fn random_cut_points(length: usize) -> (usize, usize) {
+    let a = random::thread_rng().gen_range(0, length);
+    let b = random::thread_rng().gen_range(0, length);
+    if a < b { (a, b) } else { (b, a) }
+}
+
 fn gaussian(mean: f32, deviation: f32) -> f32 {
-    let mut x1 = rand::random::<f32>();
+    let mut x1 = random::random::<f32>();
     if x1 == 0.0 {
         x1 = 1.0;
     }
     
-    let mut x2 = rand::random::<f32>();
+    let mut x2 = random::random::<f32>();
     if x2 == 0.0 {
         x2 = 1.0;
     }
@@ -94,7 +158,7 @@ fn gaussian(mean: f32, deviation: f32) -> f32 {
 // TODO: Test the dank out of this function
 pub fn gaussian_mutation(genome: &mut Vec<f32>, probability: f32, range: &Range<f32>) {
     for i in 0..genome.len() {
-        if rand::random::<f32>() > probability {
+        if random::random::<f32>() > probability {
             continue;
         }
         
@@ -102,3 +166,46 @@ pub fn gaussian_mutation(genome: &mut Vec<f32>, probability: f32, range: &Range<
         genome[i] = gaussian(genome[i], delta_factor);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genetic::helpers::is_permutation;
+
+    fn assert_keeps_permutations(mutation: fn(&mut Vec<i32>, f32, &Range<i32>)) {
+        for _ in 0..500 {
+            let size = random::thread_rng().gen_range(1, 40);
+            let mut genome: Vec<i32> = (0..size as i32).collect();
+            random::thread_rng().shuffle(&mut genome);
+
+            let probability = random::random::<f32>();
+            mutation(&mut genome, probability, &Range::new(0, size as i32 - 1));
+            assert!(is_permutation(&genome), "{:?}", genome);
+        }
+    }
+
+    #[test]
+    fn swap_position_keeps_permutations() {
+        assert_keeps_permutations(swap_position);
+    }
+
+    #[test]
+    fn inversion_mutation_keeps_permutations() {
+        assert_keeps_permutations(inversion_mutation);
+    }
+
+    #[test]
+    fn scramble_mutation_keeps_permutations() {
+        assert_keeps_permutations(scramble_mutation);
+    }
+
+    #[test]
+    fn insertion_mutation_keeps_permutations() {
+        assert_keeps_permutations(insertion_mutation);
+    }
+
+    #[test]
+    fn two_opt_mutation_keeps_permutations() {
+        assert_keeps_permutations(two_opt_mutation);
+    }
+}
@@ -0,0 +1,87 @@
+extern crate num;
+use self::num::Num;
+
+use genetic::population::{Population, Individual};
+
+// Whether a run goes on after a generation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flow {
+    Continue,
+    Stop,
+}
+
+// Callbacks into a run, registered with `Population::add_observer`. Every
+// callback does nothing by default, so observers only implement what they
+// need. Generations are the index given to `iterate_generation`.
+pub trait Observer<T>
+    where T: PartialOrd
+{
+    fn on_generation_start(&mut self, _population: &Population<T>, _generation: usize) {}
+
+    // After every fitness evaluation outside local search, with the raw fitness.
+    fn on_evaluated(&mut self, _individual: &Individual<T>, _fitness: f32) {}
+
+    // When an evaluation beats every fitness seen so far, initial population included.
+    fn on_new_best(&mut self, _individual: &Individual<T>, _fitness: f32) {}
+
+    // Indices into `population.individuals` of the parents just selected.
+    fn on_selection(&mut self, _population: &Population<T>, _selected: &[usize]) {}
+
+    // Offspring bred, mutated and improved, before they are evaluated.
+    fn on_offspring(&mut self, _population: &Population<T>, _offspring: &[Individual<T>]) {}
+
+    // Returning `Flow::Stop` ends `Population::run` after this generation.
+    fn on_generation_end(&mut self, _population: &Population<T>, _generation: usize) -> Flow {
+        Flow::Continue
+    }
+
+    // Once, when `Population::run` finishes or is stopped.
+    fn on_terminate(&mut self, _population: &Population<T>) {}
+}
+
+// Stops the run once some evaluation reached `target`, with the tolerance of
+// `Problem::is_solved`.
+pub struct TargetStop {
+    pub target: f32,
+}
+
+impl<T> Observer<T> for TargetStop
+    where T: Copy + PartialOrd + Num
+{
+    fn on_generation_end(&mut self, population: &Population<T>, _generation: usize) -> Flow {
+        if self.target - population.best_fitness() <= 1e-6 * self.target.abs().max(1.0) {
+            Flow::Stop
+        } else {
+            Flow::Continue
+        }
+    }
+}
+
+// Stops the run after `generations` generations without a new best fitness.
+pub struct StagnationStop {
+    pub generations: usize,
+    best_fitness: Option<f32>,
+    stagnant: usize,
+}
+
+impl StagnationStop {
+    pub fn new(generations: usize) -> StagnationStop {
+        StagnationStop { generations: generations, best_fitness: None, stagnant: 0 }
+    }
+}
+
+impl<T> Observer<T> for StagnationStop
+    where T: Copy + PartialOrd + Num
+{
+    fn on_generation_end(&mut self, population: &Population<T>, _generation: usize) -> Flow {
+        let best_fitness = population.best_fitness();
+        match self.best_fitness {
+            Some(previous) if best_fitness <= previous => self.stagnant += 1,
+            _ => {
+                self.best_fitness = Some(best_fitness);
+                self.stagnant = 0;
+            },
+        }
+        if self.stagnant >= self.generations { Flow::Stop } else { Flow::Continue }
+    }
+}
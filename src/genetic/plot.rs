@@ -0,0 +1,58 @@
+extern crate gnuplot;
+use self::gnuplot::{AxesCommon, Figure, Color};
+use self::gnuplot::LabelOption::TextColor;
+use self::gnuplot::PlotOption::LineWidth;
+
+extern crate num;
+use self::num::Num;
+
+use genetic::population::Population;
+
+// Convergence and diversity plots of a finished run, shown with gnuplot.
+pub fn show_plots<T>(population: &Population<T>)
+    where T: Copy + PartialOrd + Num
+{
+    show_convergence_plot(&population.average_fitness_in_generation,
+                          &population.best_fitness_in_generation);
+    
+    show_diversity_plot(&population.diversity_in_generation);
+}
+
+pub fn show_convergence_plot(average_fitnesses: &Vec<f32>, best_fitnesses: &Vec<f32>) {
+    let generations: Vec<usize> = (0..average_fitnesses.len()).collect();
+
+    let mut fg = Figure::new();
+    fg.set_terminal("wxt size 800, 400", "");
+    {
+        let axes = fg.axes2d()
+            .lines(&generations,
+                   average_fitnesses,
+                   &[Color("#505050"), LineWidth(1.2)])
+            .lines(&generations,
+                   best_fitnesses,
+                   &[Color("#0072bd"), LineWidth(1.2)]);
+
+        let label_options = &[TextColor("black")];
+        axes.set_x_label("Gerações", label_options);
+        axes.set_y_label("Fitness", label_options);
+    }
+    fg.show();
+} 
+
+pub fn show_diversity_plot(diversity_in_generations: &Vec<f32>) {
+    let generations: Vec<usize> = (0..diversity_in_generations.len()).collect();
+
+    let mut fg = Figure::new();
+    fg.set_terminal("wxt size 800, 400", "");
+    {
+        let axes = fg.axes2d()
+            .lines(&generations,
+                   diversity_in_generations,
+                   &[Color("#505050"), LineWidth(1.2)]);
+    
+        let label_options = &[TextColor("black")];
+        axes.set_x_label("Gerações", label_options);
+        axes.set_y_label("Diversidade", label_options);
+    }
+    fg.show();
+}
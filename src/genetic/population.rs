@@ -1,7 +1,7 @@
 extern crate rand;
 pub use self::rand::distributions;
 use self::rand::distributions::IndependentSample;
-use self::rand::{thread_rng, Rng};
+use self::rand::Rng;
 
 
 extern crate num;
@@ -11,18 +11,42 @@ use self::num::traits::pow;
 use std::fmt;
 use std::cmp;
 use std::ops::{Add, Sub, Mul};
-use std::collections::HashSet;
+use std::collections::{HashSet, HashMap};
+use std::cell::Cell;
+use std::mem;
 use std::f32;
 
 use genetic::fitness::HasFitness;
 use genetic::mutation::Mutation;
+use genetic::local_search::{LocalSearch, WriteBack};
+use genetic::genealogy::{Genealogy, Lineage};
+use genetic::credit::OperatorStatistics;
+use genetic::portfolio::{Portfolio, CrossoverFunction, MutationFunction};
+use genetic::diversity::{self, DiversityMeasure, DiversityReport};
+use genetic::observer::{Observer, Flow};
+use genetic::scaling::{FitnessScaling, LinearScaling};
+use genetic::replacement::{ReplacementMode, ReplacementPolicy, Elitism, SurvivorSelection, GenerationGap,
+                           ParentSelection};
 
 use genetic::helpers::{SimpleStepRange, Range};
+use genetic::random::{self, thread_rng};
 
 // Individual Stuff
 #[derive(Debug, Clone)]
 pub struct Individual<T> {
     pub genome: Vec<T>,
+    // Fitness found by local search, or inherited with an unchanged genome,
+    // used instead of evaluating the genome until the genome changes.
+    pub learned_fitness: Option<f32>,
+    // Raw fitness once the population evaluated the genome, so survivors and
+    // elites are not evaluated again. Cleared whenever the genome changes.
+    pub fitness: Option<f32>,
+    // Value of the population's `births` counter when this individual was bred.
+    // Unique within a population: the initial individuals are births 0..size.
+    pub birth: usize,
+    // Set for every bred individual; the initial ones only get one while the
+    // population tracks its genealogy.
+    pub lineage: Option<Lineage>,
 }
 
 impl<T> Individual<T>
@@ -32,14 +56,23 @@ impl<T> Individual<T>
         where T: rand::Rand + rand::distributions::range::SampleRange + Add + Num
     {
         let mut genome: Vec<T> = Vec::new();
-        let mut rng = rand::thread_rng();
-        let range_dist = distributions::Range::new(range.start, range.end + T::one());
+        if !(range.start < range.end) {
+            genome.resize(size, range.start);
+            return Individual::<T> { genome: genome, learned_fitness: None, fitness: None, birth: 0, lineage: None };
+        }
+
+        // Integer genes are drawn from start..=end, real ones uniformly from
+        // [start, end): there is no next value to stop before for them.
+        let integral = T::one() / (T::one() + T::one()) == T::zero();
+        let end = if integral { range.end + T::one() } else { range.end };
+        let mut rng = random::thread_rng();
+        let range_dist = distributions::Range::new(range.start, end);
         for _ in 0..size {
             let value = range_dist.ind_sample(&mut rng);
             genome.push(value);
         }
 
-        Individual::<T> { genome: genome }
+        Individual::<T> { genome: genome, learned_fitness: None, fitness: None, birth: 0, lineage: None }
     }
 
     pub fn new_ordered(size: usize) -> Individual<i32>
@@ -49,10 +82,19 @@ impl<T> Individual<T>
         for i in 0..size {
             genome.push(i as i32);
         }
-        let mut rng = rand::thread_rng();
+        let mut rng = random::thread_rng();
         rng.shuffle(&mut genome);
 
-        Individual::<i32> { genome: genome }
+        Individual::<i32> { genome: genome, learned_fitness: None, fitness: None, birth: 0, lineage: None }
+    }
+
+    // Replaces the genome, forgetting the cached fitness if it changed.
+    fn set_genome(&mut self, genome: Vec<T>) {
+        if self.genome != genome {
+            self.genome = genome;
+            self.learned_fitness = None;
+            self.fitness = None;
+        }
     }
 }
 
@@ -66,6 +108,16 @@ pub struct Population<T>
     pub best_fitness_in_generation: Vec<f32>,
     pub average_fitness_in_generation: Vec<f32>,
     pub diversity_in_generation: Vec<f32>,
+    // Evaluations spent so far when each generation was recorded.
+    pub evaluations_in_generation: Vec<usize>,
+    // Fitness function calls, local search included. This is the measure to
+    // compare runs with different replacement modes by.
+    pub evaluations: usize,
+    // Breeding steps taken so far: one per generation in generational mode,
+    // one per group of offspring in steady-state mode.
+    pub steps: usize,
+    // Offspring bred so far.
+    pub births: usize,
     genome_length: usize,
     crossover_probability: f32,
     mutation_probability: f32,
@@ -78,15 +130,44 @@ pub struct Population<T>
     range: Range<T>,
 
     diversity_function: fn(&Vec<T>, &Vec<T>, &Range<T>) -> f32,
-    fitness_function: fn(&Vec<T>, &Range<T>) -> f32,
-    crossover_function: fn(&Vec<T>, &Vec<T>) -> (Vec<T>, Vec<T>),
-    mutation_function: fn(&mut Vec<T>, f32, &Range<T>),
+    diversity_measure: DiversityMeasure,
+    fitness_function: Box<Fn(&Vec<T>, &Range<T>) -> f32>,
+    crossover: Portfolio<CrossoverFunction<T>>,
+    mutation: Portfolio<MutationFunction<T>>,
+
+    local_search: Option<Box<LocalSearch<T>>>,
+    local_search_probability: f32,
+    local_search_budget: usize,
+    write_back: WriteBack,
+
+    scaling: Box<FitnessScaling>,
+    generation_gap: GenerationGap,
+
+    replacement: ReplacementMode,
+    parent_selection: ParentSelection,
+    elitism: Elitism,
+    survivor_selection: SurvivorSelection,
+    genealogy: Option<Genealogy>,
+    operator_statistics: HashMap<&'static str, OperatorStatistics>,
+    // Raw fitness of the best parent of offspring not evaluated yet, by birth.
+    parent_fitness_of: HashMap<usize, f32>,
+    // Births and operators of the offspring bred this generation, and of the
+    // ones bred last generation that made it into this one.
+    offspring_in_generation: Vec<(usize, Vec<&'static str>)>,
+    offspring_on_trial: Vec<(usize, Vec<&'static str>)>,
+    observers: Vec<Box<Observer<T>>>,
+    // Best raw fitness evaluated so far.
+    best_fitness: f32,
+
+    // Whether `fitnesses` holds the raw fitness of the current individuals,
+    // so the next generation does not have to evaluate them again.
+    fitnesses_evaluated: bool,
 }
 
 impl<T> Population<T>
     where T: Copy + PartialOrd + Num
 {
-    pub fn new(size: usize,
+    pub fn new<F>(size: usize,
                genome_size: usize,
                crossover_probability: f32,
                mutation_probability: f32,
@@ -97,19 +178,24 @@ impl<T> Population<T>
                has_fitness_sharing: bool,
                crowding_factor: usize,
                diversity_function: fn(&Vec<T>, &Vec<T>, &Range<T>) -> f32,
-               fitness_function: fn(&Vec<T>, &Range<T>) -> f32,
+               fitness_function: F,
                crossover_function: fn(&Vec<T>, &Vec<T>) -> (Vec<T>, Vec<T>),
                mutation_function: fn(&mut Vec<T>, f32, &Range<T>))
                -> Population<T>
-        where T: rand::Rand + rand::distributions::range::SampleRange
+        where T: rand::Rand + rand::distributions::range::SampleRange,
+              F: Fn(&Vec<T>, &Range<T>) -> f32 + 'static
     {
         let mut individuals: Vec<Individual<T>> = Vec::new();
         let mut fitnesses: Vec<f32> = Vec::new();
 
         for i in 0..size {
             individuals.push(Individual::<T>::new(genome_size, &range));
-            fitnesses.push(individuals[i].genome.fitness(&fitness_function, &range));
+            individuals[i].birth = i;
+            let fitness = individuals[i].genome.fitness(&fitness_function, &range);
+            individuals[i].fitness = Some(fitness);
+            fitnesses.push(fitness);
         }
+        let best_fitness = fitnesses.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
     
         Population::<T> {
             individuals: individuals,
@@ -118,6 +204,10 @@ impl<T> Population<T>
             best_fitness_in_generation: Vec::<f32>::new(),
             average_fitness_in_generation: Vec::<f32>::new(),
             diversity_in_generation: Vec::<f32>::new(),
+            evaluations_in_generation: Vec::<usize>::new(),
+            evaluations: size,
+            steps: 0,
+            births: size,
             range: range,
             crossover_probability: crossover_probability,
             mutation_probability: mutation_probability,
@@ -129,13 +219,36 @@ impl<T> Population<T>
             crowding_factor: crowding_factor,
 
             diversity_function: diversity_function,
-            fitness_function: fitness_function,
-            crossover_function: crossover_function,
-            mutation_function: mutation_function,
+            diversity_measure: DiversityMeasure::Pairwise,
+            fitness_function: Box::new(fitness_function),
+            crossover: Portfolio::single("crossover", crossover_function),
+            mutation: Portfolio::single("mutation", mutation_function),
+
+            local_search: None,
+            local_search_probability: 0.0,
+            local_search_budget: 0,
+            write_back: WriteBack::Lamarckian,
+
+            scaling: Box::new(LinearScaling::default()),
+            generation_gap: GenerationGap::Progressive,
+
+            replacement: ReplacementMode::Generational,
+            parent_selection: ParentSelection::Tournament(4),
+            elitism: Elitism::Count(1),
+            survivor_selection: SurvivorSelection::Replace,
+            genealogy: None,
+            operator_statistics: HashMap::new(),
+            parent_fitness_of: HashMap::new(),
+            offspring_in_generation: Vec::new(),
+            offspring_on_trial: Vec::new(),
+            observers: Vec::new(),
+            best_fitness: best_fitness,
+
+            fitnesses_evaluated: true,
         }
     }
 
-    pub fn new_ordered(size: usize,
+    pub fn new_ordered<F>(size: usize,
                        genome_size: usize,
                        crossover_probability: f32,
                        mutation_probability: f32,
@@ -146,11 +259,12 @@ impl<T> Population<T>
                        has_fitness_sharing: bool,
                        crowding_factor: usize,
                        diversity_function: fn(&Vec<i32>, &Vec<i32>, &Range<i32>) -> f32,
-                       fitness_function: fn(&Vec<i32>, &Range<i32>) -> f32,
+                       fitness_function: F,
                        crossover_function: fn(&Vec<i32>, &Vec<i32>) -> (Vec<i32>, Vec<i32>),
                        mutation_function: fn(&mut Vec<i32>, f32, &Range<i32>))
                        -> Population<i32>
-        where T: rand::Rand + rand::distributions::range::SampleRange
+        where T: rand::Rand + rand::distributions::range::SampleRange,
+              F: Fn(&Vec<i32>, &Range<i32>) -> f32 + 'static
     {
         let mut population = Population::<i32>::new(size,
                                                     genome_size,
@@ -167,24 +281,214 @@ impl<T> Population<T>
                                                     crossover_function,
                                                     mutation_function);
 
-        {
-            let mut individuals = &mut population.individuals;
-            let mut fitnesses = &mut population.fitnesses;
-            for i in 0..individuals.len() {
-                individuals[i] = Individual::<i32>::new_ordered(genome_size);
-                fitnesses[i] = individuals[i].genome.fitness(&fitness_function, &range);
-            }
+        for i in 0..population.individuals.len() {
+            population.individuals[i] = Individual::<i32>::new_ordered(genome_size);
+            population.individuals[i].birth = i;
+            population.fitnesses[i] = population.individuals[i].genome.fitness(&*population.fitness_function,
+                                                                               &range);
+            population.individuals[i].fitness = Some(population.fitnesses[i]);
+            population.evaluations += 1;
         }
+        population.best_fitness = population.fitnesses.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
         population
             
     }
 
-    pub fn iterate_generation(&mut self, current_generation: usize, total_generations: usize) {
-        let progress_factor = current_generation as f32 / total_generations as f32;
-        
-        self.compute_fitnesses();
+    // Turns the run into a memetic algorithm: after mutation, each offspring is
+    // improved with `probability`, spending up to `budget` evaluations.
+    pub fn set_local_search<L>(&mut self, local_search: L, probability: f32, budget: usize, write_back: WriteBack)
+        where L: LocalSearch<T> + 'static
+    {
+        self.local_search = Some(Box::new(local_search));
+        self.local_search_probability = probability;
+        self.local_search_budget = budget;
+        self.write_back = write_back;
+    }
+
+    // Replaces the default linear scaling and turns scaling on.
+    pub fn set_scaling<S>(&mut self, scaling: S)
+        where S: FitnessScaling + 'static
+    {
+        self.scaling = Box::new(scaling);
+        self.has_scaling = true;
+    }
+
+    // Replaces the default progressive schedule and turns the gap on.
+    pub fn set_generation_gap(&mut self, generation_gap: GenerationGap) {
+        self.generation_gap = generation_gap;
+        self.has_generation_gap = true;
+    }
+
+    // Starts recording every individual bred from now on, with the current
+    // individuals as the roots. Costs memory for every individual ever bred.
+    pub fn enable_genealogy(&mut self) {
+        let mut genealogy = Genealogy::new();
+        let generation = self.best_fitness_in_generation.len();
+        for i in 0..self.individuals.len() {
+            let lineage = Lineage { parents: Vec::new(), generation: generation, operators: Vec::new() };
+            genealogy.record(self.individuals[i].birth, &lineage);
+            if self.fitnesses_evaluated {
+                genealogy.set_fitness(self.individuals[i].birth, self.fitnesses[i]);
+            }
+            self.individuals[i].lineage = Some(lineage);
+        }
+        self.genealogy = Some(genealogy);
+    }
+
+    pub fn genealogy(&self) -> Option<&Genealogy> {
+        self.genealogy.as_ref()
+    }
+
+    // Replaces the single crossover function given to the constructor. Fails,
+    // leaving the current one, for an empty portfolio.
+    pub fn set_crossover_portfolio(&mut self, portfolio: Portfolio<CrossoverFunction<T>>) -> Result<(), String> {
+        if portfolio.len() == 0 {
+            return Err("the crossover portfolio is empty".to_string());
+        }
+        self.crossover = portfolio;
+        Ok(())
+    }
+
+    // Replaces the single mutation function given to the constructor. Fails,
+    // leaving the current one, for an empty portfolio.
+    pub fn set_mutation_portfolio(&mut self, portfolio: Portfolio<MutationFunction<T>>) -> Result<(), String> {
+        if portfolio.len() == 0 {
+            return Err("the mutation portfolio is empty".to_string());
+        }
+        self.mutation = portfolio;
+        Ok(())
+    }
+
+    pub fn crossover_portfolio(&self) -> &Portfolio<CrossoverFunction<T>> {
+        &self.crossover
+    }
+
+    pub fn mutation_portfolio(&self) -> &Portfolio<MutationFunction<T>> {
+        &self.mutation
+    }
+
+    // Fails, leaving the current mode, when a steady-state step cannot be run:
+    // it mates two distinct individuals and keeps one or both children.
+    pub fn set_replacement(&mut self, replacement: ReplacementMode) -> Result<(), String> {
+        if let ReplacementMode::SteadyState { offspring_per_step, .. } = replacement {
+            if offspring_per_step < 1 || offspring_per_step > 2 {
+                return Err("steady-state replacement breeds 1 or 2 offspring per step".to_string());
+            }
+            if self.individuals.len() < 2 {
+                return Err("steady-state replacement needs at least two individuals".to_string());
+            }
+        }
+        self.replacement = replacement;
+        Ok(())
+    }
+
+    // Overrides the single elite `has_elitism` keeps; a count of zero turns
+    // elitism off.
+    pub fn set_elitism(&mut self, elitism: Elitism) {
+        self.has_elitism = elitism.count(self.individuals.len()) > 0;
+        self.elitism = elitism;
+    }
+
+    // Pairwise by default. Only the recorded diversity changes: sharing,
+    // crowding and replacement keep using the diversity function.
+    pub fn set_diversity_measure(&mut self, diversity_measure: DiversityMeasure) {
+        self.diversity_measure = diversity_measure;
+    }
+
+    // Fails, leaving the current selection, for an empty tournament.
+    pub fn set_parent_selection(&mut self, parent_selection: ParentSelection) -> Result<(), String> {
+        if parent_selection == ParentSelection::Tournament(0) {
+            return Err("a tournament needs at least one individual".to_string());
+        }
+        self.parent_selection = parent_selection;
+        Ok(())
+    }
+
+    // Fails, leaving the current selection, when lambda is too small for it.
+    pub fn set_survivor_selection(&mut self, survivor_selection: SurvivorSelection) -> Result<(), String> {
+        match survivor_selection {
+            SurvivorSelection::Plus { offspring } if offspring == 0 =>
+                return Err("(mu + lambda) needs at least one offspring".to_string()),
+            SurvivorSelection::Comma { offspring } if offspring < self.individuals.len() =>
+                return Err(format!("(mu, lambda) needs at least {} offspring, one per individual",
+                                   self.individuals.len())),
+            _ => {},
+        }
+        self.survivor_selection = survivor_selection;
+        Ok(())
+    }
+
+    fn elite_count(&self) -> usize {
+        if self.has_elitism { self.elitism.count(self.individuals.len()) } else { 0 }
+    }
+
+    pub fn add_observer<O>(&mut self, observer: O)
+        where O: Observer<T> + 'static
+    {
+        self.observers.push(Box::new(observer));
+    }
+
+    // Best raw fitness evaluated so far, initial population included.
+    pub fn best_fitness(&self) -> f32 {
+        self.best_fitness
+    }
 
-        // Save average and best fitness in this generation
+    // Runs up to `total_generations` generations, fewer if an observer stops
+    // the run, and returns how many ran. The final population is recorded as
+    // well, so the per-generation statistics end with the last offspring.
+    pub fn run(&mut self, total_generations: usize) -> usize {
+        let mut generations = 0;
+        while generations < total_generations {
+            let flow = self.iterate_generation(generations, total_generations);
+            generations += 1;
+            if flow == Flow::Stop {
+                break;
+            }
+        }
+        if !self.fitnesses_evaluated {
+            self.compute_fitnesses();
+        }
+        self.record_generation();
+        info!("run finished generations={} evaluations={} best={}", generations, self.evaluations, self.best_fitness);
+        self.notify(|observer, population| { observer.on_terminate(population); Flow::Continue });
+        generations
+    }
+
+    // Returns `Flow::Stop` when an observer asked to stop after this generation.
+    pub fn iterate_generation(&mut self, current_generation: usize, total_generations: usize) -> Flow {
+        self.notify(|observer, population| {
+            observer.on_generation_start(population, current_generation);
+            Flow::Continue
+        });
+        match self.replacement {
+            ReplacementMode::Generational => self.iterate_generational(current_generation, total_generations),
+            ReplacementMode::SteadyState { offspring_per_step, policy } =>
+                self.iterate_steady_state(offspring_per_step, policy),
+        }
+        self.notify(|observer, population| observer.on_generation_end(population, current_generation))
+    }
+
+    // Hands every observer to `event` along with the population; stops if
+    // any of them does.
+    fn notify<F>(&mut self, mut event: F) -> Flow
+        where F: FnMut(&mut Observer<T>, &Population<T>) -> Flow
+    {
+        if self.observers.is_empty() {
+            return Flow::Continue;
+        }
+        let mut observers = mem::replace(&mut self.observers, Vec::new());
+        let mut flow = Flow::Continue;
+        for observer in observers.iter_mut() {
+            if event(&mut **observer, self) == Flow::Stop {
+                flow = Flow::Stop;
+            }
+        }
+        self.observers = observers;
+        flow
+    }
+
+    // Save average and best fitness in this generation
+    fn record_generation(&mut self) {
         {
             let mut best_fitness = 0.0;
             let mut best_individual = self.individuals[0].clone();
@@ -204,11 +508,251 @@ impl<T> Population<T>
 
             let diversity = self.calculate_diversity();
             self.diversity_in_generation.push(diversity);
+            self.evaluations_in_generation.push(self.evaluations);
+
+            debug!("generation={} best={} average={} diversity={} evaluations={}",
+                   self.best_fitness_in_generation.len() - 1, best_fitness, avg_fitness, diversity, self.evaluations);
+        }
+    }
+
+    fn iterate_steady_state(&mut self, offspring_per_step: usize, policy: ReplacementPolicy) {
+        if !self.fitnesses_evaluated {
+            self.compute_fitnesses();
+        }
+        self.record_generation();
+
+        let mut bred = 0;
+        while bred < self.individuals.len() {
+            let dad_index = self.select_fit_individual();
+            let mom_index = self.select_fit_individual_except(dad_index);
+            self.notify(|observer, population| {
+                observer.on_selection(population, &[dad_index, mom_index]);
+                Flow::Continue
+            });
+
+            let (dad_birth, mom_birth) = (self.individuals[dad_index].birth, self.individuals[mom_index].birth);
+            let (dad_fitness, mom_fitness) = (self.fitnesses[dad_index], self.fitnesses[mom_index]);
+
+            let crossover = if random::random::<f32>() <= self.crossover_probability {
+                Some(self.crossover.choose())
+            } else {
+                None
+            };
+            let (boy_genome, girl_genome) = match crossover {
+                Some(operator) => {
+                    trace!("crossover operator={} parents={},{}", self.crossover.name(operator), dad_birth, mom_birth);
+                    (self.crossover.function(operator))(&self.individuals[dad_index].genome,
+                                                        &self.individuals[mom_index].genome)
+                },
+                None => (self.individuals[dad_index].genome.clone(), self.individuals[mom_index].genome.clone()),
+            };
+
+            let mut offspring = Vec::new();
+            for (genome, parent_index) in vec![boy_genome, girl_genome].into_iter()
+                    .zip(vec![dad_index, mom_index])
+                    .take(offspring_per_step) {
+                let mut child = Individual::<T> { genome: genome, learned_fitness: None, fitness: None, birth: 0, lineage: None };
+                match crossover {
+                    Some(operator) => self.give_birth(&mut child,
+                                                      vec![dad_birth, mom_birth],
+                                                      vec![self.crossover.name(operator)],
+                                                      dad_fitness.max(mom_fitness)),
+                    None => {
+                        child.fitness = self.individuals[parent_index].fitness;
+                        let (parent_birth, parent_fitness) = (self.individuals[parent_index].birth,
+                                                              self.fitnesses[parent_index]);
+                        self.give_birth(&mut child, vec![parent_birth], Vec::new(), parent_fitness)
+                    },
+                }
+                self.mutate_child(&mut child);
+                offspring.push(child);
+            }
+            self.improve_offspring(&mut offspring);
+            self.register_offspring(&offspring);
+            self.notify(|observer, population| { observer.on_offspring(population, &offspring); Flow::Continue });
+
+            for (mut child, parent_index) in offspring.into_iter().zip(vec![dad_index, mom_index]) {
+                let child_fitness = self.evaluate(&mut child);
+
+                if let Some(victim_index) = self.select_victim(policy, &child, child_fitness, parent_index) {
+                    self.individuals[victim_index] = child;
+                    self.fitnesses[victim_index] = child_fitness;
+                }
+            }
+            bred += offspring_per_step;
+            self.steps += 1;
+        }
+        self.credit_survivors();
+    }
+
+    fn select_victim(&self,
+                     policy: ReplacementPolicy,
+                     child: &Individual<T>,
+                     child_fitness: f32,
+                     parent_index: usize) -> Option<usize> {
+        let size = self.individuals.len();
+        let victim_index = match policy {
+            ReplacementPolicy::Worst =>
+                (0..size).fold(0, |worst, i| if self.fitnesses[i] < self.fitnesses[worst] { i } else { worst }),
+            ReplacementPolicy::Random => random::thread_rng().gen_range(0, size),
+            ReplacementPolicy::Oldest =>
+                (0..size).fold(0, |oldest, i| if self.individuals[i].birth < self.individuals[oldest].birth { i } else { oldest }),
+            ReplacementPolicy::MostSimilar => {
+                let distance = |i: usize| (self.diversity_function)(&self.individuals[i].genome, &child.genome, &self.range);
+                (0..size).fold(0, |closest, i| if distance(i) < distance(closest) { i } else { closest })
+            },
+            ReplacementPolicy::Parent => {
+                if child_fitness < self.fitnesses[parent_index] {
+                    return None;
+                }
+                parent_index
+            },
+        };
+
+        // Elites are only displaced by fitter children
+        let victim_fitness = self.fitnesses[victim_index];
+        if child_fitness < victim_fitness {
+            let fitter_individuals = self.fitnesses.iter().filter(|&&fitness| fitness > victim_fitness).count();
+            if fitter_individuals < self.elite_count() {
+                return None;
+            }
+        }
+        Some(victim_index)
+    }
+
+    // Gives `child` the next birth and its lineage. `parent_fitness` is the
+    // raw fitness of its best parent, which the child is credited against.
+    fn give_birth(&mut self,
+                  child: &mut Individual<T>,
+                  parents: Vec<usize>,
+                  operators: Vec<&'static str>,
+                  parent_fitness: f32) {
+        // A genome copied unchanged from its parent keeps the parent's fitness
+        child.learned_fitness = child.fitness.take();
+        child.birth = self.births;
+        self.births += 1;
+
+        child.lineage = Some(Lineage {
+            parents: parents,
+            generation: self.best_fitness_in_generation.len().saturating_sub(1),
+            operators: operators,
+        });
+        self.parent_fitness_of.insert(child.birth, parent_fitness);
+    }
+
+    // Mutates `child` with an operator picked from the portfolio, noting it in
+    // its lineage when the genome changed. Costs a genome copy and comparison
+    // per offspring, as the operator statistics and rewards are always kept.
+    fn mutate_child(&mut self, child: &mut Individual<T>) {
+        let operator = self.mutation.choose();
+        let original_genome = child.genome.clone();
+        child.genome.mutate(&self.mutation.function(operator), self.mutation_probability, &self.range);
+
+        if original_genome != child.genome {
+            child.learned_fitness = None;
+            trace!("mutation operator={} child={}", self.mutation.name(operator), child.birth);
+            if let Some(ref mut lineage) = child.lineage {
+                lineage.operators.push(self.mutation.name(operator));
+            }
+        }
+    }
+
+    // Records finished offspring in the genealogy and the operator statistics.
+    fn register_offspring(&mut self, offspring: &[Individual<T>]) {
+        for child in offspring {
+            if let Some(ref lineage) = child.lineage {
+                if let Some(ref mut genealogy) = self.genealogy {
+                    genealogy.record(child.birth, lineage);
+                }
+                for &operator in &lineage.operators {
+                    self.operator_statistics.entry(operator).or_insert_with(OperatorStatistics::default).applications += 1;
+                }
+                self.offspring_in_generation.push((child.birth, lineage.operators.clone()));
+            }
+        }
+    }
+
+    // Credits the operators of last generation's offspring that outlived this
+    // generation's replacement, and puts this generation's offspring on trial.
+    // Offspring already gone will never be evaluated.
+    fn credit_survivors(&mut self) {
+        let present: HashSet<usize> = self.individuals.iter().map(|individual| individual.birth).collect();
+        for (birth, operators) in self.offspring_on_trial.drain(..) {
+            if present.contains(&birth) {
+                for operator in operators {
+                    self.operator_statistics.entry(operator).or_insert_with(OperatorStatistics::default).survivors += 1;
+                }
+            }
+        }
+        for (birth, operators) in self.offspring_in_generation.drain(..) {
+            if present.contains(&birth) {
+                self.offspring_on_trial.push((birth, operators));
+            } else {
+                self.parent_fitness_of.remove(&birth);
+            }
+        }
+    }
+
+    // Per operator, sorted by name.
+    pub fn operator_statistics(&self) -> Vec<(&'static str, OperatorStatistics)> {
+        let mut statistics: Vec<(&'static str, OperatorStatistics)> = self.operator_statistics.iter()
+            .map(|(&operator, statistics)| (operator, statistics.clone()))
+            .collect();
+        statistics.sort_by(|a, b| a.0.cmp(b.0));
+        statistics
+    }
+
+    // Memetic step, see `set_local_search`.
+    fn improve_offspring(&mut self, offspring: &mut [Individual<T>]) {
+        let evaluations = Cell::new(0);
+        if let Some(ref local_search) = self.local_search {
+            let fitness_function = &self.fitness_function;
+            let range = &self.range;
+            let fitness = |genome: &Vec<T>| {
+                evaluations.set(evaluations.get() + 1);
+                fitness_function(genome, range)
+            };
+
+            for individual in offspring.iter_mut() {
+                if random::random::<f32>() > self.local_search_probability {
+                    continue;
+                }
+
+                let mut genome = individual.genome.clone();
+                let learned_fitness = match local_search.improve(&mut genome, &fitness, self.local_search_budget, range) {
+                    Some(learned_fitness) => learned_fitness,
+                    None => continue,
+                };
+                match self.write_back {
+                    WriteBack::Lamarckian => {
+                        individual.genome = genome;
+                        individual.learned_fitness = Some(learned_fitness);
+                    },
+                    WriteBack::Baldwinian => individual.learned_fitness = Some(learned_fitness),
+                }
+                trace!("local search child={} fitness={}", individual.birth, learned_fitness);
+                if let Some(ref mut lineage) = individual.lineage {
+                    lineage.operators.push("local search");
+                }
+            }
         }
+        self.evaluations += evaluations.get();
+    }
 
-        let fittest_index = self.get_fittest_individual();
-        let fittest_individual = self.individuals[fittest_index].clone();
-        let fittest_fitness = self.fitnesses[fittest_index];
+    fn iterate_generational(&mut self, current_generation: usize, total_generations: usize) {
+        let progress_factor = current_generation as f32 / total_generations as f32;
+        
+        if !self.fitnesses_evaluated {
+            self.compute_fitnesses();
+        }
+        self.record_generation();
+        self.steps += 1;
+
+        let parent_fitnesses = self.fitnesses.clone();
+        let elites: Vec<(Individual<T>, f32)> = self.fittest_indices(self.elite_count()).into_iter()
+            .map(|i| (self.individuals[i].clone(), self.fitnesses[i]))
+            .collect();
+        let first_offspring_birth = self.births;
         
         if self.has_fitness_sharing && progress_factor < 0.9 {
             for i in 0..self.individuals.len() {
@@ -236,44 +780,110 @@ impl<T> Population<T>
         }
         
         if self.has_scaling {
-            let c = 1.2 * (2.0 / 1.2 as f32).powf(current_generation as f32 / total_generations as f32);
-            self.apply_linear_scaling(c); 
+            self.scaling.scale(&mut self.fitnesses, progress_factor);
         }
+        self.fitnesses_evaluated = false;
+
+        let offspring_count = match self.survivor_selection {
+            SurvivorSelection::Replace => self.individuals.len(),
+            SurvivorSelection::Plus { offspring } | SurvivorSelection::Comma { offspring } => offspring,
+        };
 
         // Selection
         let mut new_individuals = Vec::new();
-        for _ in 0..self.individuals.len() {
+        let mut parent_best = Vec::new();
+        let mut selected = Vec::with_capacity(offspring_count);
+        for _ in 0..offspring_count {
             let fit_index = self.select_fit_individual();
             new_individuals.push(self.individuals[fit_index].clone());
+            parent_best.push(parent_fitnesses[fit_index]);
+            selected.push(fit_index);
         } 
+        self.notify(|observer, population| { observer.on_selection(population, &selected); Flow::Continue });
+        let mut parents: Vec<Vec<usize>> = new_individuals.iter().map(|individual| vec![individual.birth]).collect();
+        let mut crossovers: Vec<Vec<&'static str>> = vec![Vec::new(); offspring_count];
         
-        for _ in SimpleStepRange(0, self.individuals.len(), 2) {
-            if rand::random::<f32>() > self.crossover_probability {
+        for _ in SimpleStepRange(0, offspring_count, 2) {
+            // A single offspring, as in (mu + 1), has no one to mate with
+            if offspring_count < 2 || random::random::<f32>() > self.crossover_probability {
                 continue;
             }
             
             // let dad_index = self.select_random_individual();
             // let mom_index = self.select_random_individual_except(dad_index);
-            let (dad_index, mom_index) = self.select_random_couple();
+            let (dad_index, mom_index) = self.select_random_couple(offspring_count);
 
             //println!("dad: {}, mom: {}", dad_index, mom_index);
-            let (boy_genome, girl_genome) = self.crossover(&mut new_individuals, dad_index, mom_index);
-            new_individuals[dad_index].genome.clone_from(&boy_genome);
-            new_individuals[mom_index].genome.clone_from(&girl_genome);
+            let (boy_genome, girl_genome, operator) = self.crossover(&mut new_individuals, dad_index, mom_index);
+            new_individuals[dad_index].set_genome(boy_genome);
+            new_individuals[mom_index].set_genome(girl_genome);
+
+            let mut couple: Vec<usize> = parents[dad_index].iter().chain(parents[mom_index].iter()).cloned().collect();
+            couple.sort();
+            couple.dedup();
+            parents[dad_index] = couple.clone();
+            parents[mom_index] = couple;
+            let couple_best = parent_best[dad_index].max(parent_best[mom_index]);
+            parent_best[dad_index] = couple_best;
+            parent_best[mom_index] = couple_best;
+            for &index in [dad_index, mom_index].iter() {
+                if !crossovers[index].contains(&operator) {
+                    crossovers[index].push(operator);
+                }
+            }
         }
         
-        for individual in &mut new_individuals {
-            individual.genome.mutate(&self.mutation_function,
-                                     self.mutation_probability,
-                                     &self.range);
+        for (i, individual) in new_individuals.iter_mut().enumerate() {
+            self.give_birth(individual, parents[i].clone(), crossovers[i].clone(), parent_best[i]);
+            self.mutate_child(individual);
+        }
+        self.improve_offspring(&mut new_individuals);
+        self.register_offspring(&new_individuals);
+        self.notify(|observer, population| { observer.on_offspring(population, &new_individuals); Flow::Continue });
+
+        match self.survivor_selection {
+            SurvivorSelection::Replace => self.replace_parents(new_individuals, progress_factor),
+            SurvivorSelection::Plus { .. } => {
+                let offspring_fitnesses: Vec<f32> = new_individuals.iter_mut().map(|child| self.evaluate(child)).collect();
+                let parents: Vec<Individual<T>> = self.individuals.drain(..).collect();
+                let pool = parents.into_iter().zip(parent_fitnesses)
+                    .chain(new_individuals.into_iter().zip(offspring_fitnesses))
+                    .collect();
+                self.keep_fittest(pool);
+            },
+            SurvivorSelection::Comma { .. } => {
+                let offspring_fitnesses: Vec<f32> = new_individuals.iter_mut().map(|child| self.evaluate(child)).collect();
+                self.keep_fittest(new_individuals.into_iter().zip(offspring_fitnesses).collect());
+            },
         }
 
-        if self.has_generation_gap && progress_factor < 0.9 {
-            let gap_factor = (10.0 * progress_factor).ceil() / 10.0;
-            
-            let last_index = (gap_factor * self.individuals.len() as f32).ceil() as usize; 
-            
-            let mut indices: Vec<usize> = num::range(0, last_index).collect();
+        if !elites.is_empty() {
+            if !self.fitnesses_evaluated {
+                self.compute_fitnesses();
+            }
+            self.insert_elites(elites, first_offspring_birth);
+        }
+        self.credit_survivors();
+    }
+
+    // Places the offspring over the parents, through the generation gap and
+    // crowding when they are on.
+    fn replace_parents(&mut self, new_individuals: Vec<Individual<T>>, progress_factor: f32) {
+        let gap = if self.has_generation_gap {
+            let initial_diversity = self.diversity_in_generation.first().cloned().unwrap_or(0.0);
+            let diversity = self.diversity_in_generation.last().cloned().unwrap_or(0.0);
+            let relative_diversity = if initial_diversity > 0.0 { diversity / initial_diversity } else { 1.0 };
+            self.generation_gap.fraction(progress_factor, relative_diversity)
+        } else {
+            None
+        };
+
+        if let Some(gap_factor) = gap {
+            let last_index = cmp::min((gap_factor * self.individuals.len() as f32).ceil() as usize,
+                                      self.individuals.len());
+
+            // The gap replaces `last_index` distinct random slots, each with the offspring bred for it
+            let mut indices: Vec<usize> = num::range(0, self.individuals.len()).collect();
             
             let mut shuffled_indices = indices.as_mut_slice();
             thread_rng().shuffle(&mut shuffled_indices);
@@ -282,7 +892,7 @@ impl<T> Population<T>
                 for i in 0..last_index {
                     let curr_index = shuffled_indices[i];
                     let mut similar_index = curr_index;
-                    let mut similar_similarity = 1.0;
+                    let mut similar_similarity = f32::NEG_INFINITY;
                     
                     for j in self.select_random_n_indices(self.crowding_factor) {
                         let similarity = 1.0 - (self.diversity_function)(&self.individuals[j].genome,
@@ -322,15 +932,54 @@ impl<T> Population<T>
                 self.individuals.clone_from(&new_individuals);
             }
         }
+    }
 
-        
-        if self.has_elitism {            
-            self.individuals[fittest_index] = fittest_individual.clone();
-            self.fitnesses[fittest_index] = fittest_fitness;
-        }
+    // Survivor selection for (mu + lambda) and (mu, lambda): the best mu of `pool`.
+    fn keep_fittest(&mut self, mut pool: Vec<(Individual<T>, f32)>) {
+        let size = self.fitnesses.len();
+        pool.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(cmp::Ordering::Equal));
+        pool.truncate(size);
 
-        
+        let (individuals, fitnesses) = pool.into_iter().unzip();
+        self.individuals = individuals;
+        self.fitnesses = fitnesses;
+        self.fitnesses_evaluated = true;
+    }
+
+    // Puts back the elites that did not survive, each over the worst offspring
+    // it is fitter than. Offspring are told apart by their birth.
+    fn insert_elites(&mut self, elites: Vec<(Individual<T>, f32)>, first_offspring_birth: usize) {
+        for (elite, elite_fitness) in elites {
+            if self.individuals.iter().any(|individual| individual.birth == elite.birth) {
+                continue;
+            }
+
+            let mut worst_offspring: Option<usize> = None;
+            for i in 0..self.individuals.len() {
+                if self.individuals[i].birth < first_offspring_birth {
+                    continue;
+                }
+                match worst_offspring {
+                    Some(worst) if self.fitnesses[worst] <= self.fitnesses[i] => {},
+                    _ => worst_offspring = Some(i),
+                }
+            }
 
+            if let Some(worst) = worst_offspring {
+                if self.fitnesses[worst] < elite_fitness {
+                    self.individuals[worst] = elite;
+                    self.fitnesses[worst] = elite_fitness;
+                }
+            }
+        }
+    }
+
+    // Indices of the `count` fittest individuals, fittest first.
+    fn fittest_indices(&self, count: usize) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.individuals.len()).collect();
+        indices.sort_by(|&a, &b| self.fitnesses[b].partial_cmp(&self.fitnesses[a]).unwrap_or(cmp::Ordering::Equal));
+        indices.truncate(count);
+        indices
     }
 
     fn select_random_n_indices(&self, num_indices: usize) -> Vec<usize> {
@@ -338,7 +987,7 @@ impl<T> Population<T>
         
         for _ in 0..num_indices {
             loop {
-                let candidate_index = rand::thread_rng().gen_range(0, self.individuals.len());
+                let candidate_index = random::thread_rng().gen_range(0, self.individuals.len());
                 let mut is_unique = true;
                 for index in &selected_individuals {
                     if candidate_index == *index {
@@ -356,30 +1005,39 @@ impl<T> Population<T>
         selected_individuals 
     }
 
-    fn select_random_couple(&self) -> (usize, usize) {
-        let dad_index = rand::thread_rng().gen_range(0, self.individuals.len());
+    fn select_random_couple(&self, size: usize) -> (usize, usize) {
+        let dad_index = random::thread_rng().gen_range(0, size);
         let mut mom_index = dad_index;
         
         while mom_index == dad_index {            
-            mom_index = rand::thread_rng().gen_range(0, self.individuals.len());
+            mom_index = random::thread_rng().gen_range(0, size);
         }
         (dad_index, mom_index)
     }
     
     fn select_fit_individual(&self) -> usize {
-        self.tournament(4)
+        match self.parent_selection {
+            ParentSelection::Tournament(size) => self.tournament(size, None),
+            ParentSelection::Roulette => self.roulette(None),
+        }
     }
 
+    // Needs at least two individuals.
     fn select_fit_individual_except(&self, dad_index: usize) -> usize {
-        let mut mom_index: usize;
-        loop {
-            mom_index = self.select_fit_individual();
+        match self.parent_selection {
+            ParentSelection::Tournament(size) => self.tournament(size, Some(dad_index)),
+            ParentSelection::Roulette => self.roulette(Some(dad_index)),
+        }
+    }
 
-            if mom_index != dad_index {
-                break;
+    fn select_random_individual_except(&self, except: Option<usize>) -> usize {
+        let mut rng = random::thread_rng();
+        loop {
+            let picked = rng.gen_range(0, self.individuals.len());
+            if Some(picked) != except {
+                return picked;
             }
         }
-        mom_index
     }
 
     fn get_fittest_individual(&self) -> usize {
@@ -394,34 +1052,90 @@ impl<T> Population<T>
     }
 
     fn compute_fitnesses(&mut self) {
-        for i in 0..self.individuals.len() {
-            self.fitnesses[i] = self.individuals[i].genome.fitness(&self.fitness_function,
-                                                                   &self.range);
+        let mut individuals = mem::replace(&mut self.individuals, Vec::new());
+        for i in 0..individuals.len() {
+            self.fitnesses[i] = self.evaluate(&mut individuals[i]);
+        }
+        self.individuals = individuals;
+        self.fitnesses_evaluated = true;
+    }
+
+    // Raw fitness of `individual`, counting the evaluation unless it is cached
+    // or already learned. Only the first evaluation of a genome is reported.
+    fn evaluate(&mut self, individual: &mut Individual<T>) -> f32 {
+        if let Some(fitness) = individual.fitness {
+            return fitness;
         }
+        let fitness = match individual.learned_fitness {
+            Some(learned_fitness) => learned_fitness,
+            None => {
+                self.evaluations += 1;
+                individual.genome.fitness(&*self.fitness_function, &self.range)
+            },
+        };
+        if let Some(ref mut genealogy) = self.genealogy {
+            genealogy.set_fitness(individual.birth, fitness);
+        }
+        for observer in self.observers.iter_mut() {
+            observer.on_evaluated(individual, fitness);
+        }
+        if fitness > self.best_fitness {
+            info!("new best fitness={} individual={} evaluations={}", fitness, individual.birth, self.evaluations);
+            self.best_fitness = fitness;
+            for observer in self.observers.iter_mut() {
+                observer.on_new_best(individual, fitness);
+            }
+        }
+        if let (Some(parent_fitness), Some(lineage)) = (self.parent_fitness_of.remove(&individual.birth),
+                                                        individual.lineage.as_ref()) {
+            let reward = if fitness > parent_fitness { 1.0 } else { 0.0 };
+            for &operator in &lineage.operators {
+                let statistics = self.operator_statistics.entry(operator).or_insert_with(OperatorStatistics::default);
+                statistics.evaluated += 1;
+                statistics.total_delta += fitness - parent_fitness;
+                if fitness > parent_fitness {
+                    statistics.improvements += 1;
+                }
+                self.crossover.reward(operator, reward);
+                self.mutation.reward(operator, reward);
+            }
+        }
+        individual.fitness = Some(fitness);
+        fitness
     }
 
-    fn crossover(&mut self, individuals: &mut Vec<Individual<T>>, index_dad: usize, index_mom: usize) -> (Vec<T>, Vec<T>) {
+    // Crosses two individuals with an operator picked from the portfolio, and
+    // returns the children with the operator's name.
+    fn crossover(&mut self,
+                 individuals: &mut Vec<Individual<T>>,
+                 index_dad: usize,
+                 index_mom: usize) -> (Vec<T>, Vec<T>, &'static str) {
         let max_index = cmp::max(index_dad, index_mom);
         let min_index = cmp::min(index_dad, index_mom);
 
         let (split_left, split_right) = individuals.split_at_mut(max_index);
         let (dad, mom) = (&mut split_left[min_index], &mut split_right[0]);
 
-        let (boy_genome, girl_genome) = (self.crossover_function)(&dad.genome, &mom.genome);
-        (boy_genome, girl_genome)
+        let operator = self.crossover.choose();
+        trace!("crossover operator={} parents={},{}", self.crossover.name(operator), dad.birth, mom.birth);
+        let (boy_genome, girl_genome) = (self.crossover.function(operator))(&dad.genome, &mom.genome);
+        (boy_genome, girl_genome, self.crossover.name(operator))
     }
     
 
-    fn tournament(&self, k: usize) -> usize {
-        let mut biggest: usize = rand::thread_rng().gen_range(0, self.individuals.len());
+    // Leaves out `except`, so the winner is never that individual.
+    fn tournament(&self, k: usize, except: Option<usize>) -> usize {
+        let mut rng = random::thread_rng();
+        let mut biggest = self.select_random_individual_except(except);
         let mut processed_candidates = HashSet::<usize>::new();
         processed_candidates.insert(biggest); 
-        let mut rng = rand::thread_rng();
 
-        while processed_candidates.len() < k - 1 {
+        // A population smaller than the tournament has no more candidates to draw
+        let candidates = cmp::min(k - 1, self.individuals.len() - except.map_or(0, |_| 1));
+        while processed_candidates.len() < candidates {
             let picked = rng.gen_range(0, self.individuals.len());
 
-            if processed_candidates.contains(&picked) {
+            if processed_candidates.contains(&picked) || Some(picked) == except {
                 continue;
             }
 
@@ -435,113 +1149,174 @@ impl<T> Population<T>
         biggest
     }
 
+    // Mean pairwise distance under the population's diversity measure.
     fn calculate_diversity(&self) -> f32 {
-        let mut total_diversity = 0.0;
-
-        for i in 0..self.individuals.len() {
-            for j in i..self.individuals.len() {
-                total_diversity += (self.diversity_function)(&self.individuals[i].genome,
-                                                             &self.individuals[j].genome,
-                                                             &self.range);
-            }
+        match self.diversity_measure {
+            DiversityMeasure::Pairwise =>
+                diversity::mean_pairwise_distance(&self.individuals, self.diversity_function, &self.range),
+            DiversityMeasure::Hamming => diversity::mean_hamming_distance(&self.individuals),
         }
-
-        total_diversity
     }
 
-    fn roulette(&self) -> usize {
-        let chance = rand::random::<f32>();
-        let sum = self.fitnesses.iter().fold(0.0, |acc, &x| acc + x);
+    pub fn diversity_report(&self) -> DiversityReport {
+        DiversityReport {
+            mean_pairwise_distance: self.calculate_diversity(),
+            mean_entropy: diversity::mean_entropy(&self.individuals),
+            unique_genotypes: diversity::unique_genotypes(&self.individuals),
+        }
+    }
 
-        let mut winner: usize = 0;
-        let mut last_probability = 0.0;
-        for i in 0..self.fitnesses.len() {
+    // Fitness-proportional selection on the current, possibly scaled, fitnesses.
+    // Leaves out `except` like `tournament`. Negative fitness counts as zero,
+    // and parents are picked uniformly when no fitness is positive.
+    fn roulette(&self, except: Option<usize>) -> usize {
+        let weight = |i: usize| {
             let fitness = self.fitnesses[i];
-            let probability = fitness / sum + last_probability;
+            if Some(i) == except || !fitness.is_finite() || fitness < 0.0 { 0.0 } else { fitness }
+        };
+        let total: f32 = (0..self.individuals.len()).map(&weight).sum();
+        if !(total > 0.0) || !total.is_finite() {
+            return self.select_random_individual_except(except);
+        }
 
-            if chance < probability {
-                winner = i;
-                break;
+        let mut spin = random::random::<f32>() * total;
+        let mut last_positive = 0;
+        for i in 0..self.individuals.len() {
+            let share = weight(i);
+            if share > 0.0 {
+                last_positive = i;
+                spin -= share;
+                if spin < 0.0 {
+                    return i;
+                }
             }
-
-            last_probability = probability;
         }
-        winner
+        // Rounding left some of the wheel over
+        last_positive
     }
 
-    fn apply_linear_scaling(&mut self, c: f32) {
-        let sum = self.fitnesses.iter().fold(0.0, |acc, &x| acc + x);
-        let average = sum / self.fitnesses.len() as f32;
+    // Every genome and its fitness, one debug log line each.
+    pub fn log_individuals(&self)
+        where T: fmt::Debug
+    {
+        for (individual, fitness) in self.individuals.iter().zip(&self.fitnesses) {
+            debug!("{:?} : {}", individual.genome, fitness);
+        }
+    }
 
-        let mut min = self.fitnesses[0];
-        let mut max = self.fitnesses[0];        
-        for fitness in &self.fitnesses {
-            if *fitness < min {
-                min = *fitness;
-            }
+    // "Best genome: ... : fitness" for the last recorded generation, for the
+    // caller to show. Genes outside the range are logged as a warning.
+    pub fn best_individual_diagnostic(&self) -> Option<String>
+        where T: fmt::Debug
+    {
+        let best_individual = self.best_individual_in_generation.last()?;
+        let best_fitness = self.best_fitness_in_generation.last()?;
 
-            if *fitness > max {
-                max = *fitness;
-            }
+        let is_valid = best_individual.genome.iter().all(|gene| *gene <= self.range.end && *gene >= self.range.start);
+        if !is_valid {
+            warn!("best individual {} has genes outside {:?}..{:?}",
+                  best_individual.birth, self.range.start, self.range.end);
         }
+        Some(format!("Best genome: {:?} : {}", best_individual.genome, best_fitness))
+    }
+}
 
-        let decider = (c * (average - max)) / (c - 1.0);
-        let alpha: f32;
-        let beta: f32;
 
-        if min > decider {
-            alpha = (average * (c - 1.0)) / (max - average);
-            beta  = (average * (max - c * average)) / (max - average); 
-        } else {
-            alpha = average / (average - min);
-            beta = (-min * average) / (average - min);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genetic::crossover::one_point_crossover;
+    use genetic::helpers::hamming_distance;
+    use genetic::mutation::bit_flip;
+    use genetic::portfolio::OperatorSelection;
 
-        for i in 0..self.fitnesses.len() {
-            self.fitnesses[i] = alpha * self.fitnesses[i] + beta;
-        }        
-    } 
-    
+    fn onemax_population(size: usize, crossover_probability: f32, mutation_probability: f32) -> Population<u8> {
+        Population::new(size, 20, crossover_probability, mutation_probability, Range::new(0, 1),
+                        true, false, false, false, 1, hamming_distance,
+                        |genome: &Vec<u8>, _: &Range<u8>| genome.iter().map(|&gene| gene as f32).sum(),
+                        one_point_crossover, bit_flip)
+    }
 
-    pub fn print(&self)
-        where T: fmt::Debug
-    {
-        for i in 0..self.individuals.len() {
-            let individual = &self.individuals[i];
-            let fitness = self.fitnesses[i];
-            println!("{:?} : {}", individual.genome, fitness);
-            // println!("Best vector: {:?}", population.best_fitness_in_generation);
-            // println!("Average vector: {:?}", population.average_fitness_in_generation);
-            // if let Some(best_fitness) = population.best_fitness_in_generation.last() {
-            //     println!("The Best: {}", best_fitness);
-            // }
+    #[test]
+    fn unchanged_individuals_are_not_evaluated_again() {
+        let mut population = onemax_population(10, 0.0, 0.0);
+        population.run(5);
+        assert_eq!(population.evaluations, 10);
+
+        let mut population = onemax_population(10, 0.0, 1.0);
+        population.run(5);
+        assert_eq!(population.evaluations, 60);
+    }
+
+    #[test]
+    fn steady_state_settings_are_checked() {
+        let mut population = onemax_population(10, 0.9, 0.05);
+        for &offspring_per_step in [0, 3].iter() {
+            let replacement = ReplacementMode::SteadyState { offspring_per_step: offspring_per_step,
+                                                             policy: ReplacementPolicy::Worst };
+            assert!(population.set_replacement(replacement).is_err());
         }
+        assert_eq!(population.replacement, ReplacementMode::Generational);
+        let replacement = ReplacementMode::SteadyState { offspring_per_step: 1, policy: ReplacementPolicy::Worst };
+        assert!(population.set_replacement(replacement).is_ok());
 
+        let mut population = onemax_population(1, 0.9, 0.05);
+        assert!(population.set_replacement(replacement).is_err());
     }
 
-    pub fn print_best_individual_diagnostic(&self)
-        where T: fmt::Debug
-    {
-        if let Some(best_individual) = self.best_individual_in_generation.last() {
-            if let Some(best_fitness) = self.best_fitness_in_generation.last() {
-                println!("Best genome: {:?} : {}", best_individual.genome, best_fitness);
-            }
+    #[test]
+    fn populations_smaller_than_the_tournament_still_breed() {
+        let mut population = onemax_population(2, 0.9, 0.05);
+        population.run(3);
+        let replacement = ReplacementMode::SteadyState { offspring_per_step: 2, policy: ReplacementPolicy::Worst };
+        population.set_replacement(replacement).unwrap();
+        population.run(3);
+        assert_eq!(population.best_fitness_in_generation.len(), 8);
+    }
 
-            let mut is_valid = true;
+    #[test]
+    fn roulette_is_proportional_to_fitness() {
+        let mut population = onemax_population(3, 0.9, 0.05);
+        population.set_parent_selection(ParentSelection::Roulette).unwrap();
+        population.fitnesses = vec![0.0, -1.0, 5.0];
+        for _ in 0..100 {
+            assert_eq!(population.select_fit_individual(), 2);
+            assert!(population.select_fit_individual_except(2) < 2);
+        }
+        assert!(population.set_parent_selection(ParentSelection::Tournament(0)).is_err());
+    }
 
-            for gene in &best_individual.genome {
-                if *gene > self.range.end || *gene < self.range.start {
-                    is_valid = false;
-                }
-            }
+    #[test]
+    fn hamming_measure_matches_the_pairwise_one() {
+        let mut population = onemax_population(20, 0.9, 0.05);
+        let pairwise = population.calculate_diversity();
+        population.set_diversity_measure(DiversityMeasure::Hamming);
+        assert!((population.calculate_diversity() - pairwise).abs() < 1e-6);
+    }
 
-            if is_valid == false {
-                println!("***Invalid best individual***");
-            }
-            
+    #[test]
+    fn empty_portfolios_are_rejected() {
+        let mut population = onemax_population(10, 0.9, 0.05);
+        assert!(population.set_crossover_portfolio(Portfolio::new(OperatorSelection::Fixed)).is_err());
+        assert!(population.set_mutation_portfolio(Portfolio::new(OperatorSelection::Fixed)).is_err());
+        assert_eq!(population.crossover_portfolio().len(), 1);
+        assert_eq!(population.mutation_portfolio().len(), 1);
+    }
+
+    #[test]
+    fn integer_genes_cover_both_ends_of_the_range() {
+        let genome = Individual::<i32>::new(2000, &Range::new(-1, 1)).genome;
+        for value in -1..2 {
+            assert!(genome.contains(&value), "{} never drawn", value);
         }
+        assert!(genome.iter().all(|&gene| gene >= -1 && gene <= 1));
+    }
 
-        //println!("Diversity test! {}", self.compute_diversity());
-    } 
+    #[test]
+    fn real_genes_stay_within_the_range() {
+        let genome = Individual::<f32>::new(2000, &Range::new(-5.12, 5.12)).genome;
+        assert!(genome.iter().all(|&gene| gene >= -5.12 && gene < 5.12));
+        // Nothing piles up on the upper bound
+        assert!(genome.iter().filter(|&&gene| gene > 5.0).count() < 100);
+    }
 }
-
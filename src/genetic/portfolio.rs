@@ -0,0 +1,216 @@
+use std::f32;
+
+use genetic::helpers::Range;
+use genetic::random;
+
+pub type CrossoverFunction<T> = fn(&Vec<T>, &Vec<T>) -> (Vec<T>, Vec<T>);
+pub type MutationFunction<T> = fn(&mut Vec<T>, f32, &Range<T>);
+
+// How a portfolio picks its operator for each application. Rewards are 1 for
+// an offspring fitter than its best parent and 0 otherwise, so they do not
+// depend on the scale of the fitness function.
+//  - Fixed: in proportion to the weights, never adapting.
+//  - ProbabilityMatching: each operator keeps a quality, the running average
+//    of its rewards with `adaptation_rate`, and is picked in proportion to it,
+//    never less often than `min_probability`.
+//  - AdaptivePursuit: like probability matching, but the probabilities chase
+//    the current best operator at `learning_rate`, which gets
+//    `1 - (operators - 1) * min_probability` while the rest get `min_probability`.
+//  - Ucb: UCB1 bandit, the best mean reward plus `exploration` times the
+//    usual confidence bonus.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperatorSelection {
+    Fixed,
+    ProbabilityMatching { min_probability: f32, adaptation_rate: f32 },
+    AdaptivePursuit { min_probability: f32, adaptation_rate: f32, learning_rate: f32 },
+    Ucb { exploration: f32 },
+}
+
+// Weighted set of named operators, see `Population::set_crossover_portfolio`.
+// The names label the operators in lineages and operator statistics.
+#[derive(Clone)]
+pub struct Portfolio<F> {
+    names: Vec<&'static str>,
+    functions: Vec<F>,
+    // Weights as given, the starting point of `probabilities`.
+    weights: Vec<f32>,
+    probabilities: Vec<f32>,
+    qualities: Vec<f32>,
+    // Applications, counted when an operator is picked.
+    picks: Vec<usize>,
+    // Rewards received and their sum, counted when offspring are evaluated.
+    rewards: Vec<usize>,
+    total_rewards: Vec<f32>,
+    selection: OperatorSelection,
+}
+
+impl<F: Copy> Portfolio<F> {
+    pub fn new(selection: OperatorSelection) -> Portfolio<F> {
+        Portfolio {
+            names: Vec::new(),
+            functions: Vec::new(),
+            weights: Vec::new(),
+            probabilities: Vec::new(),
+            qualities: Vec::new(),
+            picks: Vec::new(),
+            rewards: Vec::new(),
+            total_rewards: Vec::new(),
+            selection: selection,
+        }
+    }
+
+    // A portfolio that always applies `function`.
+    pub fn single(name: &'static str, function: F) -> Portfolio<F> {
+        Portfolio::new(OperatorSelection::Fixed).add(name, function, 1.0)
+    }
+
+    // Adds an operator, failing when one of that name is already in. Weights
+    // are relative and set the starting probabilities of the adaptive
+    // selections as well.
+    pub fn with(self, name: &'static str, function: F, weight: f32) -> Result<Portfolio<F>, String> {
+        if self.contains(name) {
+            return Err(format!("operator {} is already in the portfolio", name));
+        }
+        Ok(self.add(name, function, weight))
+    }
+
+    fn add(mut self, name: &'static str, function: F, weight: f32) -> Portfolio<F> {
+        self.names.push(name);
+        self.functions.push(function);
+        self.weights.push(weight.max(0.0));
+        self.qualities.push(1.0);
+        self.picks.push(0);
+        self.rewards.push(0);
+        self.total_rewards.push(0.0);
+
+        let total_weight: f32 = self.weights.iter().sum();
+        let count = self.weights.len() as f32;
+        self.probabilities = self.weights.iter()
+            .map(|&weight| if total_weight > 0.0 { weight / total_weight } else { 1.0 / count })
+            .collect();
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|&known| known == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, operator: usize) -> &'static str {
+        self.names[operator]
+    }
+
+    pub fn function(&self, operator: usize) -> F {
+        self.functions[operator]
+    }
+
+    // Not used by `OperatorSelection::Ucb`, which keeps its initial weights.
+    pub fn probabilities(&self) -> &Vec<f32> {
+        &self.probabilities
+    }
+
+    pub fn picks(&self) -> &Vec<usize> {
+        &self.picks
+    }
+
+    // Picks the operator for one application.
+    pub fn choose(&mut self) -> usize {
+        assert!(!self.names.is_empty(), "empty operator portfolio");
+        let operator = match self.selection {
+            OperatorSelection::Ucb { exploration } => self.upper_confidence_bound(exploration),
+            _ => self.roulette(),
+        };
+        self.picks[operator] += 1;
+        operator
+    }
+
+    // Rewards the operator called `name`, if it belongs to this portfolio.
+    pub fn reward(&mut self, name: &str, reward: f32) {
+        let operator = match self.names.iter().position(|&operator| operator == name) {
+            Some(operator) => operator,
+            None => return,
+        };
+        self.rewards[operator] += 1;
+        self.total_rewards[operator] += reward;
+
+        match self.selection {
+            OperatorSelection::Fixed | OperatorSelection::Ucb { .. } => {},
+            OperatorSelection::ProbabilityMatching { min_probability, adaptation_rate } => {
+                self.update_quality(operator, reward, adaptation_rate);
+                let min_probability = self.clamp_min_probability(min_probability);
+                let total_quality: f32 = self.qualities.iter().sum();
+                let count = self.len() as f32;
+                for i in 0..self.len() {
+                    let share = if total_quality > 0.0 { self.qualities[i] / total_quality } else { 1.0 / count };
+                    self.probabilities[i] = min_probability + (1.0 - count * min_probability) * share;
+                }
+            },
+            OperatorSelection::AdaptivePursuit { min_probability, adaptation_rate, learning_rate } => {
+                self.update_quality(operator, reward, adaptation_rate);
+                let min_probability = self.clamp_min_probability(min_probability);
+                let max_probability = 1.0 - (self.len() - 1) as f32 * min_probability;
+                let best = (0..self.len()).fold(0, |best, i| if self.qualities[i] > self.qualities[best] { i } else { best });
+                for i in 0..self.len() {
+                    let target = if i == best { max_probability } else { min_probability };
+                    self.probabilities[i] += learning_rate * (target - self.probabilities[i]);
+                }
+            },
+        }
+    }
+
+    fn update_quality(&mut self, operator: usize, reward: f32, adaptation_rate: f32) {
+        self.qualities[operator] += adaptation_rate * (reward - self.qualities[operator]);
+    }
+
+    // `min_probability` can not exceed an even split.
+    fn clamp_min_probability(&self, min_probability: f32) -> f32 {
+        min_probability.max(0.0).min(1.0 / self.len() as f32)
+    }
+
+    fn roulette(&self) -> usize {
+        let chance = random::random::<f32>();
+        let mut cumulative = 0.0;
+        for i in 0..self.len() {
+            cumulative += self.probabilities[i];
+            if chance < cumulative {
+                return i;
+            }
+        }
+        self.len() - 1
+    }
+
+    // Operators never picked go first, then the highest mean reward plus bonus.
+    fn upper_confidence_bound(&self, exploration: f32) -> usize {
+        if let Some(unpicked) = self.picks.iter().position(|&picks| picks == 0) {
+            return unpicked;
+        }
+
+        let total_picks: usize = self.picks.iter().sum();
+        let score = |i: usize| {
+            let mean = if self.rewards[i] > 0 { self.total_rewards[i] / self.rewards[i] as f32 } else { 0.0 };
+            mean + exploration * (2.0 * (total_picks as f32).ln() / self.picks[i] as f32).sqrt()
+        };
+        (0..self.len()).fold(0, |best, i| if score(i) > score(best) { i } else { best })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(genome: &mut Vec<u8>, _: f32) { genome[0] = 0; }
+    fn last(genome: &mut Vec<u8>, _: f32) { genome[1] = 0; }
+
+    #[test]
+    fn operator_names_are_unique() {
+        let portfolio = Portfolio::new(OperatorSelection::Fixed).with("first", first as fn(&mut Vec<u8>, f32), 1.0)
+            .and_then(|portfolio| portfolio.with("last", last, 1.0))
+            .unwrap();
+        assert_eq!(portfolio.len(), 2);
+        assert!(portfolio.with("first", last, 2.0).is_err());
+    }
+}
+
@@ -0,0 +1,221 @@
+use std::f32::consts::PI;
+
+use genetic::helpers::Range;
+use genetic::problems::Problem;
+use genetic::random;
+
+// Classic continuous minimization benchmarks. Each entry knows its default
+// bounds and dimensionality and, where it is known, its global minimum.
+#[derive(Clone)]
+pub struct Benchmark {
+    pub name: &'static str,
+    pub dimensions: usize,
+    pub bounds: Range<f32>,
+    fixed_dimensions: bool,
+    function: fn(&Vec<f32>) -> f32,
+    minimum: fn(usize) -> Option<f32>,
+}
+
+impl Benchmark {
+    // Fails for zero dimensions, or for a function only defined in its own.
+    pub fn with_dimensions(mut self, dimensions: usize) -> Result<Benchmark, String> {
+        if dimensions == 0 {
+            return Err(format!("{} needs at least one dimension", self.name));
+        }
+        if self.fixed_dimensions && dimensions != self.dimensions {
+            return Err(format!("{} is only defined in {} dimensions", self.name, self.dimensions));
+        }
+        self.dimensions = dimensions;
+        Ok(self)
+    }
+
+    pub fn with_bounds(mut self, bounds: Range<f32>) -> Benchmark {
+        self.bounds = bounds;
+        self
+    }
+
+    // Raw objective value. Genes are clamped to the bounds first, because
+    // mutations such as the gaussian one can step outside them.
+    pub fn evaluate(&self, genome: &Vec<f32>) -> f32 {
+        let clamped: Vec<f32> = genome.iter()
+            .map(|gene| gene.max(self.bounds.start).min(self.bounds.end))
+            .collect();
+        (self.function)(&clamped)
+    }
+
+    pub fn minimum(&self) -> Option<f32> {
+        (self.minimum)(self.dimensions)
+    }
+}
+
+impl Problem<f32> for Benchmark {
+    // Maps the objective onto (0, 1], reaching 1.0 at the global minimum.
+    // Without a known minimum `exp(-f)` keeps the ordering and stays positive.
+    fn fitness(&self, genome: &Vec<f32>) -> f32 {
+        let value = self.evaluate(genome);
+        match self.minimum() {
+            Some(minimum) => 1.0 / (1.0 + (value - minimum).max(0.0)),
+            None => (-value).exp(),
+        }
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        self.minimum().map(|_| 1.0)
+    }
+}
+
+//////////////
+// Registry //
+//////////////
+
+pub fn benchmarks() -> Vec<Benchmark> {
+    vec![entry("sphere", 3, -5.12, 5.12, sphere, zero),
+         entry("rosenbrock", 2, -2.048, 2.048, rosenbrock, zero),
+         entry("step", 5, -5.12, 5.12, step, zero),
+         entry("quartic", 30, -1.28, 1.28, noisy_quartic, zero),
+         Benchmark { fixed_dimensions: true, ..entry("foxholes", 2, -65.536, 65.536, foxholes, foxholes_minimum) },
+         entry("rastrigin", 10, -5.12, 5.12, rastrigin, zero),
+         entry("ackley", 10, -32.768, 32.768, ackley, zero),
+         entry("griewank", 10, -600.0, 600.0, griewank, zero),
+         entry("schwefel", 10, -500.0, 500.0, schwefel, zero),
+         entry("levy", 10, -10.0, 10.0, levy, zero),
+         entry("michalewicz", 2, 0.0, PI, michalewicz, michalewicz_minimum),
+         entry("styblinski-tang", 10, -5.0, 5.0, styblinski_tang, styblinski_tang_minimum)]
+}
+
+// Looks a benchmark up by name; De Jong's F1-F5 are also known as "dejong1"...
+pub fn benchmark(name: &str) -> Option<Benchmark> {
+    let name = match name {
+        "dejong1" => "sphere",
+        "dejong2" => "rosenbrock",
+        "dejong3" => "step",
+        "dejong4" => "quartic",
+        "dejong5" => "foxholes",
+        other => other,
+    };
+    benchmarks().into_iter().find(|benchmark| benchmark.name == name)
+}
+
+fn entry(name: &'static str,
+         dimensions: usize,
+         start: f32,
+         end: f32,
+         function: fn(&Vec<f32>) -> f32,
+         minimum: fn(usize) -> Option<f32>) -> Benchmark {
+    Benchmark {
+        name: name,
+        dimensions: dimensions,
+        bounds: Range::new(start, end),
+        fixed_dimensions: false,
+        function: function,
+        minimum: minimum,
+    }
+}
+
+fn zero(_: usize) -> Option<f32> {
+    Some(0.0)
+}
+
+fn foxholes_minimum(_: usize) -> Option<f32> {
+    Some(0.998004)
+}
+
+fn michalewicz_minimum(dimensions: usize) -> Option<f32> {
+    match dimensions {
+        2 => Some(-1.8013),
+        5 => Some(-4.687658),
+        10 => Some(-9.66015),
+        _ => None,
+    }
+}
+
+fn styblinski_tang_minimum(dimensions: usize) -> Option<f32> {
+    Some(-39.16617 * dimensions as f32)
+}
+
+/////////////////////////
+// Objective functions //
+/////////////////////////
+
+pub fn sphere(x: &Vec<f32>) -> f32 {
+    x.iter().map(|xi| xi * xi).sum()
+}
+
+pub fn rosenbrock(x: &Vec<f32>) -> f32 {
+    let mut sum = 0.0;
+    for i in 0..x.len().saturating_sub(1) {
+        sum += 100.0 * (x[i + 1] - x[i] * x[i]).powi(2) + (1.0 - x[i]).powi(2);
+    }
+    sum
+}
+
+// Shifted by 6 per dimension so the minimum, on [-5.12, -5), is 0.
+pub fn step(x: &Vec<f32>) -> f32 {
+    x.iter().map(|xi| xi.floor() + 6.0).sum()
+}
+
+pub fn noisy_quartic(x: &Vec<f32>) -> f32 {
+    let mut sum = 0.0;
+    for i in 0..x.len() {
+        sum += (i + 1) as f32 * x[i].powi(4);
+    }
+    sum + random::random::<f32>()
+}
+
+pub fn foxholes(x: &Vec<f32>) -> f32 {
+    let a = [-32.0, -16.0, 0.0, 16.0, 32.0];
+    let mut sum = 0.002;
+    for j in 0..25 {
+        let (a1, a2) = (a[j % 5], a[j / 5]);
+        sum += 1.0 / ((j + 1) as f32 + (x[0] - a1).powi(6) + (x[1] - a2).powi(6));
+    }
+    1.0 / sum
+}
+
+pub fn rastrigin(x: &Vec<f32>) -> f32 {
+    10.0 * x.len() as f32 + x.iter().map(|xi| xi * xi - 10.0 * (2.0 * PI * xi).cos()).sum::<f32>()
+}
+
+pub fn ackley(x: &Vec<f32>) -> f32 {
+    let n = x.len() as f32;
+    let squares: f32 = x.iter().map(|xi| xi * xi).sum();
+    let cosines: f32 = x.iter().map(|xi| (2.0 * PI * xi).cos()).sum();
+    -20.0 * (-0.2 * (squares / n).sqrt()).exp() - (cosines / n).exp() + 20.0 + 1f32.exp()
+}
+
+pub fn griewank(x: &Vec<f32>) -> f32 {
+    let mut sum = 0.0;
+    let mut product = 1.0;
+    for i in 0..x.len() {
+        sum += x[i] * x[i] / 4000.0;
+        product *= (x[i] / ((i + 1) as f32).sqrt()).cos();
+    }
+    sum - product + 1.0
+}
+
+pub fn schwefel(x: &Vec<f32>) -> f32 {
+    418.9829 * x.len() as f32 - x.iter().map(|xi| xi * xi.abs().sqrt().sin()).sum::<f32>()
+}
+
+pub fn levy(x: &Vec<f32>) -> f32 {
+    let w: Vec<f32> = x.iter().map(|xi| 1.0 + (xi - 1.0) / 4.0).collect();
+    let last = w[w.len() - 1];
+    let mut sum = (PI * w[0]).sin().powi(2);
+    for i in 0..w.len() - 1 {
+        sum += (w[i] - 1.0).powi(2) * (1.0 + 10.0 * (PI * w[i] + 1.0).sin().powi(2));
+    }
+    sum + (last - 1.0).powi(2) * (1.0 + (2.0 * PI * last).sin().powi(2))
+}
+
+pub fn michalewicz(x: &Vec<f32>) -> f32 {
+    let m = 10;
+    let mut sum = 0.0;
+    for i in 0..x.len() {
+        sum -= x[i].sin() * ((i + 1) as f32 * x[i] * x[i] / PI).sin().powi(2 * m);
+    }
+    sum
+}
+
+pub fn styblinski_tang(x: &Vec<f32>) -> f32 {
+    x.iter().map(|xi| xi.powi(4) - 16.0 * xi * xi + 5.0 * xi).sum::<f32>() / 2.0
+}
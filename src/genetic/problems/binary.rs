@@ -0,0 +1,290 @@
+extern crate rand;
+use self::rand::{Rng, SeedableRng, StdRng};
+
+use genetic::problems::Problem;
+
+// Binary benchmarks over `Population<u8>` genomes of 0s and 1s. All of them
+// report their optimum so a run can tell when it has solved the instance.
+
+pub struct OneMax {
+    pub length: usize,
+}
+
+impl Problem<u8> for OneMax {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        genome.iter().filter(|&&gene| gene == 1).count() as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(self.length as f32)
+    }
+}
+
+pub struct LeadingOnes {
+    pub length: usize,
+}
+
+impl Problem<u8> for LeadingOnes {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        genome.iter().take_while(|&&gene| gene == 1).count() as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(self.length as f32)
+    }
+}
+
+// Where the genes of one building block live.
+//  - Tight: block `i` is the contiguous genes `i * k .. (i + 1) * k`.
+//  - Loose: block `i` takes one gene every `length / k` positions, starting at
+//    `i`, the same layout `deceptive_f3s` uses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Linkage {
+    Tight,
+    Loose,
+}
+
+fn block_genes(length: usize, block_size: usize, linkage: Linkage, block: usize) -> Vec<usize> {
+    let blocks = length / block_size;
+    match linkage {
+        Linkage::Tight => (block * block_size..(block + 1) * block_size).collect(),
+        Linkage::Loose => (0..block_size).map(|j| block + j * blocks).collect(),
+    }
+}
+
+// Fully deceptive k-bit trap: a block of k ones scores k, any other block
+// scores k - 1 - ones, which pulls every hill climber towards all zeros.
+pub struct Trap {
+    pub length: usize,
+    pub k: usize,
+    pub linkage: Linkage,
+}
+
+impl Trap {
+    // Fails unless the length is a multiple of k.
+    pub fn new(length: usize, k: usize, linkage: Linkage) -> Result<Trap, String> {
+        if k == 0 || length % k != 0 {
+            return Err(format!("trap length {} must be a multiple of k = {}", length, k));
+        }
+        Ok(Trap { length: length, k: k, linkage: linkage })
+    }
+}
+
+impl Problem<u8> for Trap {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        let mut fitness = 0;
+        for block in 0..self.length / self.k {
+            let ones = block_genes(self.length, self.k, self.linkage, block).iter()
+                .filter(|&&i| genome[i] == 1)
+                .count();
+            fitness += if ones == self.k { self.k } else { self.k - 1 - ones };
+        }
+        fitness as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(self.length as f32)
+    }
+}
+
+// Royal Road R1: each block that is entirely ones is worth its size.
+pub struct RoyalRoad {
+    pub length: usize,
+    pub block_size: usize,
+}
+
+impl RoyalRoad {
+    // Fails unless the length is a multiple of the block size.
+    pub fn new(length: usize, block_size: usize) -> Result<RoyalRoad, String> {
+        if block_size == 0 || length % block_size != 0 {
+            return Err(format!("royal road length {} must be a multiple of the block size {}",
+                               length, block_size));
+        }
+        Ok(RoyalRoad { length: length, block_size: block_size })
+    }
+}
+
+impl Problem<u8> for RoyalRoad {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        genome.chunks(self.block_size)
+            .filter(|block| block.iter().all(|&gene| gene == 1))
+            .map(|block| block.len())
+            .sum::<usize>() as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(self.length as f32)
+    }
+}
+
+// Kauffman's NK landscape: gene `i` contributes a random value looked up from
+// its own allele and the alleles of `k` other genes. The same seed always
+// builds the same landscape.
+pub struct NkLandscape {
+    pub n: usize,
+    pub k: usize,
+    neighbours: Vec<Vec<usize>>,
+    contributions: Vec<Vec<f32>>,
+    optimum: Option<f32>,
+}
+
+// Largest N whose optimum is found by enumerating every genome.
+const NK_EXHAUSTIVE_LIMIT: usize = 16;
+
+// Largest K: every gene keeps a table of 2^(K + 1) contributions.
+pub const NK_MAX_K: usize = 16;
+
+impl NkLandscape {
+    // Neighbours are `k` distinct genes drawn at random.
+    pub fn new(n: usize, k: usize, seed: usize) -> Result<NkLandscape, String> {
+        check_nk_sizes(n, k)?;
+        let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+
+        let mut neighbours = Vec::with_capacity(n);
+        for i in 0..n {
+            let mut others: Vec<usize> = (0..n).filter(|&j| j != i).collect();
+            rng.shuffle(&mut others);
+            others.truncate(k);
+            neighbours.push(others);
+        }
+        Ok(NkLandscape::with_neighbours(n, k, neighbours, &mut rng))
+    }
+
+    // Neighbours are the `k` genes following each gene, wrapping around.
+    pub fn adjacent(n: usize, k: usize, seed: usize) -> Result<NkLandscape, String> {
+        check_nk_sizes(n, k)?;
+        let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+
+        let neighbours = (0..n).map(|i| (1..k + 1).map(|j| (i + j) % n).collect()).collect();
+        Ok(NkLandscape::with_neighbours(n, k, neighbours, &mut rng))
+    }
+
+    fn with_neighbours(n: usize, k: usize, neighbours: Vec<Vec<usize>>, rng: &mut StdRng) -> NkLandscape {
+        let contributions = (0..n)
+            .map(|_| (0..1 << (k + 1)).map(|_| rng.gen::<f32>()).collect())
+            .collect();
+
+        let mut landscape = NkLandscape {
+            n: n,
+            k: k,
+            neighbours: neighbours,
+            contributions: contributions,
+            optimum: None,
+        };
+        if n <= NK_EXHAUSTIVE_LIMIT {
+            landscape.optimum = Some(landscape.exhaustive_optimum());
+        }
+        landscape
+    }
+
+    fn exhaustive_optimum(&self) -> f32 {
+        let mut genome = vec![0u8; self.n];
+        let mut best = 0.0;
+        for bits in 0..1u64 << self.n {
+            for i in 0..self.n {
+                genome[i] = ((bits >> i) & 1) as u8;
+            }
+            let fitness = self.fitness(&genome);
+            if fitness > best {
+                best = fitness;
+            }
+        }
+        best
+    }
+}
+
+fn check_nk_sizes(n: usize, k: usize) -> Result<(), String> {
+    if k >= n || k > NK_MAX_K {
+        return Err(format!("NK landscapes need k smaller than n = {} and at most {}", n, NK_MAX_K));
+    }
+    Ok(())
+}
+
+impl Problem<u8> for NkLandscape {
+    // Mean contribution, so fitness lies in [0, 1).
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        let mut sum = 0.0;
+        for i in 0..self.n {
+            let mut index = genome[i] as usize;
+            for &neighbour in &self.neighbours[i] {
+                index = (index << 1) | genome[neighbour] as usize;
+            }
+            sum += self.contributions[i][index];
+        }
+        sum / self.n as f32
+    }
+
+    // Only known for N up to `NK_EXHAUSTIVE_LIMIT`.
+    fn optimum(&self) -> Option<f32> {
+        self.optimum
+    }
+}
+
+// Hierarchical if-and-only-if: every block at every level of a binary tree
+// that is all zeros or all ones scores its size.
+pub struct Hiff {
+    pub length: usize,
+}
+
+impl Hiff {
+    // Fails unless the length is a power of two.
+    pub fn new(length: usize) -> Result<Hiff, String> {
+        if !length.is_power_of_two() {
+            return Err(format!("HIFF length {} must be a power of two", length));
+        }
+        Ok(Hiff { length: length })
+    }
+}
+
+fn hiff_block(block: &[u8]) -> (usize, bool) {
+    if block.len() == 1 {
+        return (1, true);
+    }
+    let (left, right) = block.split_at(block.len() / 2);
+    let (left_fitness, left_uniform) = hiff_block(left);
+    let (right_fitness, right_uniform) = hiff_block(right);
+    let uniform = left_uniform && right_uniform && left[0] == right[0];
+    let bonus = if uniform { block.len() } else { 0 };
+    (left_fitness + right_fitness + bonus, uniform)
+}
+
+impl Problem<u8> for Hiff {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        hiff_block(&genome[..]).0 as f32
+    }
+
+    // Every level of the tree scores `length`.
+    fn optimum(&self) -> Option<f32> {
+        let levels = self.length.trailing_zeros() as usize + 1;
+        Some((levels * self.length) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructors_reject_bad_sizes() {
+        assert!(Trap::new(10, 4, Linkage::Tight).is_err());
+        assert!(Trap::new(8, 0, Linkage::Loose).is_err());
+        assert!(RoyalRoad::new(10, 0).is_err());
+        assert!(RoyalRoad::new(10, 4).is_err());
+        assert!(NkLandscape::new(4, 4, 0).is_err());
+        assert!(NkLandscape::adjacent(40, NK_MAX_K + 1, 0).is_err());
+        assert!(Hiff::new(0).is_err());
+        assert!(Hiff::new(12).is_err());
+    }
+
+    #[test]
+    fn all_ones_reaches_the_optimum() {
+        let ones = vec![1u8; 16];
+        let problems: Vec<Box<Problem<u8>>> = vec![Box::new(Trap::new(16, 4, Linkage::Tight).unwrap()),
+                                                   Box::new(Trap::new(16, 4, Linkage::Loose).unwrap()),
+                                                   Box::new(RoyalRoad::new(16, 8).unwrap()),
+                                                   Box::new(Hiff::new(16).unwrap())];
+        for problem in problems {
+            assert!(problem.is_solved(problem.fitness(&ones)));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use genetic::helpers::Range;
+use genetic::problems::{Problem, ProblemError, read_file, parse_number};
+
+// Graph coloring over `Population<i32>`: gene `i` is the color of vertex `i`,
+// drawn from `range()`. Fitness is the number of edges whose endpoints differ.
+pub struct GraphColoring {
+    pub vertices: usize,
+    pub colors: usize,
+    // 0-based endpoints.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl GraphColoring {
+    pub fn load<P: AsRef<Path>>(path: P, colors: usize) -> Result<GraphColoring, ProblemError> {
+        let contents = read_file(path)?;
+        GraphColoring::parse(&contents, colors)
+    }
+
+    // DIMACS `.col`: 'c' comment lines, a `p edge VERTICES EDGES` line and one
+    // `e U V` line per edge, with 1-based vertices.
+    pub fn parse(contents: &str, colors: usize) -> Result<GraphColoring, ProblemError> {
+        let mut vertices: Option<usize> = None;
+        let mut edges = Vec::new();
+
+        for line in contents.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.first() {
+                Some(&"p") if fields.len() >= 4 && fields[1] == "edge" => vertices = Some(parse_number(fields[2])?),
+                Some(&"e") if fields.len() >= 3 => {
+                    let (u, v): (usize, usize) = (parse_number(fields[1])?, parse_number(fields[2])?);
+                    if u == 0 || v == 0 {
+                        return Err(ProblemError::Parse(format!("vertices are 1-based in '{}'", line)));
+                    }
+                    edges.push((u - 1, v - 1));
+                },
+                Some(&"p") | Some(&"e") => return Err(ProblemError::Parse(format!("bad line '{}'", line))),
+                _ => (),
+            }
+        }
+
+        let vertices = vertices.ok_or(ProblemError::Parse("missing 'p edge' line".to_string()))?;
+        if edges.iter().any(|&(u, v)| u >= vertices || v >= vertices) {
+            return Err(ProblemError::Parse(format!("edge endpoint outside the {} vertices", vertices)));
+        }
+
+        Ok(GraphColoring { vertices: vertices, colors: colors, edges: edges })
+    }
+
+    pub fn range(&self) -> Range<i32> {
+        Range::new(0, self.colors as i32 - 1)
+    }
+
+    pub fn conflicts(&self, genome: &Vec<i32>) -> usize {
+        self.edges.iter().filter(|&&(u, v)| genome[u] == genome[v]).count()
+    }
+
+    pub fn colors_used(&self, genome: &Vec<i32>) -> usize {
+        let mut used: Vec<i32> = genome.clone();
+        used.sort();
+        used.dedup();
+        used.len()
+    }
+}
+
+impl Problem<i32> for GraphColoring {
+    fn fitness(&self, genome: &Vec<i32>) -> f32 {
+        (self.edges.len() - self.conflicts(genome)) as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(self.edges.len() as f32)
+    }
+
+    fn is_feasible(&self, genome: &Vec<i32>) -> bool {
+        self.conflicts(genome) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn petersen_is_three_colorable() {
+        let graph = GraphColoring::load("data/coloring/petersen.col", 3).unwrap();
+        assert_eq!(graph.vertices, 10);
+        assert_eq!(graph.edges.len(), 15);
+
+        let coloring = vec![0, 1, 0, 1, 2, 1, 0, 2, 2, 1];
+        assert_eq!(graph.conflicts(&coloring), 0);
+        assert_eq!(graph.colors_used(&coloring), 3);
+        assert!(graph.is_solved(graph.fitness(&coloring)));
+    }
+
+    #[test]
+    fn parse_needs_a_p_edge_line() {
+        assert!(GraphColoring::parse("p col 2 1\ne 1 2\n", 2).is_err());
+        assert!(GraphColoring::parse("e 1 2\n", 2).is_err());
+        assert!(GraphColoring::parse("p edge 2 1\ne 1 3\n", 2).is_err());
+        assert!(GraphColoring::parse("p edge 2 1\ne 0 1\n", 2).is_err());
+    }
+}
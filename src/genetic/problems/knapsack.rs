@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+use std::f32;
+use std::path::Path;
+
+use genetic::problems::{Problem, ProblemError, read_file, parse_number};
+
+// What to do with selections heavier than the capacity.
+//  - Repair: drop the selected items with the worst value/weight ratio until
+//    the rest fits, and score what is left.
+//  - Penalty: subtract the overweight times the best value/weight ratio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConstraintHandling {
+    Repair,
+    Penalty,
+}
+
+// 0/1 knapsack over `Population<u8>`: gene `i` selects item `i`.
+pub struct Knapsack {
+    pub capacity: f32,
+    pub values: Vec<f32>,
+    pub weights: Vec<f32>,
+    pub handling: ConstraintHandling,
+    optimum: Option<f32>,
+    // Item indices from the worst value/weight ratio to the best.
+    repair_order: Vec<usize>,
+    max_ratio: f32,
+}
+
+impl Knapsack {
+    pub fn new(capacity: f32, values: Vec<f32>, weights: Vec<f32>) -> Knapsack {
+        assert_eq!(values.len(), weights.len(), "every item needs a value and a weight");
+        let ratio = |i: usize| if weights[i] > 0.0 { values[i] / weights[i] } else { f32::MAX };
+
+        let mut repair_order: Vec<usize> = (0..values.len()).collect();
+        repair_order.sort_by(|&a, &b| ratio(a).partial_cmp(&ratio(b)).unwrap_or(Ordering::Equal));
+        let max_ratio = (0..values.len())
+            .filter(|&i| weights[i] > 0.0)
+            .map(|i| ratio(i))
+            .fold(0.0, f32::max);
+
+        Knapsack {
+            capacity: capacity,
+            values: values,
+            weights: weights,
+            handling: ConstraintHandling::Repair,
+            optimum: None,
+            repair_order: repair_order,
+            max_ratio: max_ratio,
+        }
+    }
+
+    // Instance format: a line with the number of items and the capacity, then
+    // one `value weight` line per item. An optional final line `optimum V`
+    // gives the best known value. Lines starting with '#' are comments.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Knapsack, ProblemError> {
+        let contents = read_file(path)?;
+        Knapsack::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Knapsack, ProblemError> {
+        let mut lines = contents.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        let header: Vec<&str> = lines.next()
+            .ok_or(ProblemError::Parse("knapsack instance is empty".to_string()))?
+            .split_whitespace()
+            .collect();
+        if header.len() < 2 {
+            return Err(ProblemError::Parse("expected 'items capacity' header".to_string()));
+        }
+        let items: usize = parse_number(header[0])?;
+        let capacity: f32 = parse_number(header[1])?;
+
+        let mut values = Vec::with_capacity(items);
+        let mut weights = Vec::with_capacity(items);
+        let mut optimum = None;
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields[0] == "optimum" && fields.len() == 2 {
+                optimum = Some(parse_number(fields[1])?);
+                continue;
+            }
+            if fields.len() < 2 {
+                return Err(ProblemError::Parse(format!("bad item line '{}'", line)));
+            }
+            values.push(parse_number(fields[0])?);
+            weights.push(parse_number(fields[1])?);
+        }
+
+        let numbers = values.iter().chain(weights.iter()).chain(Some(&capacity));
+        if numbers.cloned().any(|number: f32| !number.is_finite() || number < 0.0) {
+            return Err(ProblemError::Parse("values, weights and capacity must be finite, non-negative numbers"
+                                           .to_string()));
+        }
+        if values.len() != items {
+            return Err(ProblemError::Parse(format!("expected {} items, found {}", items, values.len())));
+        }
+
+        let mut knapsack = Knapsack::new(capacity, values, weights);
+        knapsack.optimum = optimum;
+        Ok(knapsack)
+    }
+
+    pub fn with_handling(mut self, handling: ConstraintHandling) -> Knapsack {
+        self.handling = handling;
+        self
+    }
+
+    pub fn with_optimum(mut self, optimum: f32) -> Knapsack {
+        self.optimum = Some(optimum);
+        self
+    }
+
+    pub fn weight(&self, genome: &Vec<u8>) -> f32 {
+        (0..genome.len()).filter(|&i| genome[i] == 1).map(|i| self.weights[i]).sum()
+    }
+
+    pub fn value(&self, genome: &Vec<u8>) -> f32 {
+        (0..genome.len()).filter(|&i| genome[i] == 1).map(|i| self.values[i]).sum()
+    }
+
+    // Feasible copy of the selection, see `ConstraintHandling::Repair`.
+    pub fn repair(&self, genome: &Vec<u8>) -> Vec<u8> {
+        let mut repaired = genome.clone();
+        let mut weight = self.weight(genome);
+        for &i in &self.repair_order {
+            if weight <= self.capacity {
+                break;
+            }
+            if repaired[i] == 1 {
+                repaired[i] = 0;
+                weight -= self.weights[i];
+            }
+        }
+        repaired
+    }
+}
+
+impl Problem<u8> for Knapsack {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        match self.handling {
+            ConstraintHandling::Repair => self.value(&self.repair(genome)),
+            ConstraintHandling::Penalty => {
+                let overweight = (self.weight(genome) - self.capacity).max(0.0);
+                (self.value(genome) - self.max_ratio * overweight).max(0.0)
+            },
+        }
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        self.optimum
+    }
+
+    fn is_feasible(&self, genome: &Vec<u8>) -> bool {
+        self.weight(genome) <= self.capacity
+    }
+
+    fn decode(&self, genome: &Vec<u8>) -> Vec<u8> {
+        match self.handling {
+            ConstraintHandling::Repair => self.repair(genome),
+            ConstraintHandling::Penalty => genome.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn p01_optimum_is_309() {
+        let knapsack = Knapsack::load("data/knapsack/p01.txt").unwrap();
+        assert_eq!(knapsack.values.len(), 10);
+        assert_eq!(knapsack.capacity, 165.0);
+        assert_eq!(knapsack.optimum(), Some(309.0));
+
+        let optimal = vec![1, 1, 1, 1, 0, 1, 0, 0, 0, 0];
+        assert_eq!(knapsack.weight(&optimal), 165.0);
+        assert!(knapsack.is_solved(knapsack.fitness(&optimal)));
+    }
+
+    #[test]
+    fn repair_drops_the_worst_ratios_until_it_fits() {
+        let knapsack = Knapsack::load("data/knapsack/p01.txt").unwrap();
+        let everything = vec![1; 10];
+        let repaired = knapsack.decode(&everything);
+        assert!(knapsack.is_feasible(&repaired));
+        assert_eq!(knapsack.fitness(&everything), knapsack.value(&repaired));
+    }
+
+    #[test]
+    fn parse_rejects_negative_and_missing_numbers() {
+        assert!(Knapsack::parse("1 10\n-5 3\n").is_err());
+        assert!(Knapsack::parse("1 10\n5 -3\n").is_err());
+        assert!(Knapsack::parse("1 -10\n5 3\n").is_err());
+        assert!(Knapsack::parse("1 10\nNaN 3\n").is_err());
+        assert!(Knapsack::parse("2 10\n5 3\n").is_err());
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use genetic::problems::{Problem, ProblemError, Render, read_file};
+
+// The maze `path_fitness` has always been evaluated on.
+const DEFAULT_MAZE: &'static str = include_str!("../../../data/mazes/default.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    fn turn_left(self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    fn turn_right(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+}
+
+// How a gene is turned into a move.
+//  - RelativeTurn: 0 turns left, 1 keeps going, 2 turns right, then one step.
+//  - AbsoluteDirection: 0 north, 1 east, 2 south, 3 west.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveEncoding {
+    RelativeTurn,
+    AbsoluteDirection,
+}
+
+impl MoveEncoding {
+    // Largest valid gene, to build the population `Range` from.
+    pub fn max_gene(&self) -> i32 {
+        match *self {
+            MoveEncoding::RelativeTurn => 2,
+            MoveEncoding::AbsoluteDirection => 3,
+        }
+    }
+}
+
+// Text format, one row per line:
+//   '#' wall, '.' or ' ' open, 'S' start, 'G' goal.
+// Rows may be ragged; missing cells are walls, and so is everything outside.
+// Lines starting with ';' are comments.
+#[derive(Debug, Clone)]
+pub struct Maze {
+    pub width: usize,
+    pub height: usize,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+    pub encoding: MoveEncoding,
+    pub initial_direction: Direction,
+    walls: Vec<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Trace {
+    // Every position stood on, in order, starting with the start position.
+    pub path: Vec<(usize, usize)>,
+    // Wall the walk ended on, if it bumped into one. Moves off the grid are
+    // reported at the position the walker was standing on.
+    pub collision: Option<(usize, usize)>,
+    // Positions stepped on more than once, in the order the revisits happened.
+    pub revisits: Vec<(usize, usize)>,
+    pub invalid_genes: usize,
+    pub reached_goal: bool,
+    pub max_consecutive_steps: usize,
+    pub closest_distance: usize,
+}
+
+impl Maze {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Maze, ProblemError> {
+        let contents = read_file(path)?;
+        Maze::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Maze, ProblemError> {
+        let rows: Vec<&str> = contents.lines()
+            .filter(|line| !line.starts_with(';'))
+            .map(|line| line.trim_right_matches('\r'))
+            .collect();
+        let rows: Vec<&str> = match rows.iter().rposition(|row| !row.trim().is_empty()) {
+            Some(last) => rows[..last + 1].to_vec(),
+            None => return Err(ProblemError::Parse("maze is empty".to_string())),
+        };
+
+        let height = rows.len();
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let mut walls = vec![true; width * height];
+        let mut start = None;
+        let mut goal = None;
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, symbol) in row.chars().enumerate() {
+                let is_wall = match symbol {
+                    '#' => true,
+                    '.' | ' ' => false,
+                    'S' => { start = Some((x, y)); false },
+                    'G' => { goal = Some((x, y)); false },
+                    other => return Err(ProblemError::Parse(format!("unknown maze symbol '{}' at ({}, {})",
+                                                                    other, x, y))),
+                };
+                walls[y * width + x] = is_wall;
+            }
+        }
+
+        Ok(Maze {
+            width: width,
+            height: height,
+            start: start.ok_or(ProblemError::Parse("maze has no start 'S'".to_string()))?,
+            goal: goal.ok_or(ProblemError::Parse("maze has no goal 'G'".to_string()))?,
+            encoding: MoveEncoding::RelativeTurn,
+            initial_direction: Direction::South,
+            walls: walls,
+        })
+    }
+
+    pub fn default_maze() -> Maze {
+        Maze::parse(DEFAULT_MAZE).expect("default maze is valid")
+    }
+
+    pub fn with_encoding(mut self, encoding: MoveEncoding) -> Maze {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn with_initial_direction(mut self, direction: Direction) -> Maze {
+        self.initial_direction = direction;
+        self
+    }
+
+    pub fn is_wall(&self, x: usize, y: usize) -> bool {
+        x >= self.width || y >= self.height || self.walls[y * self.width + x]
+    }
+
+    fn distance_to_goal(&self, position: (usize, usize)) -> usize {
+        ((self.goal.0 as i64 - position.0 as i64).abs() +
+         (self.goal.1 as i64 - position.1 as i64).abs()) as usize
+    }
+
+    fn decode_move(&self, gene: i32, facing: Direction) -> Option<Direction> {
+        match (self.encoding, gene) {
+            (MoveEncoding::RelativeTurn, 0) => Some(facing.turn_left()),
+            (MoveEncoding::RelativeTurn, 1) => Some(facing),
+            (MoveEncoding::RelativeTurn, 2) => Some(facing.turn_right()),
+            (MoveEncoding::AbsoluteDirection, 0) => Some(Direction::North),
+            (MoveEncoding::AbsoluteDirection, 1) => Some(Direction::East),
+            (MoveEncoding::AbsoluteDirection, 2) => Some(Direction::South),
+            (MoveEncoding::AbsoluteDirection, 3) => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    // Walks the genome from the start until it hits a wall, reaches the goal or
+    // runs out of genes. Genes outside the encoding are counted and skipped.
+    pub fn walk(&self, genome: &Vec<i32>) -> Trace {
+        let mut position = self.start;
+        let mut facing = self.initial_direction;
+
+        let mut trace = Trace {
+            path: vec![position],
+            collision: None,
+            revisits: Vec::new(),
+            invalid_genes: 0,
+            reached_goal: position == self.goal,
+            max_consecutive_steps: 0,
+            closest_distance: self.distance_to_goal(position),
+        };
+        let mut visited: HashSet<(usize, usize)> = trace.path.iter().cloned().collect();
+        let mut consecutive_steps = 0;
+
+        for gene in genome {
+            if trace.reached_goal {
+                break;
+            }
+
+            facing = match self.decode_move(*gene, facing) {
+                Some(direction) => direction,
+                None => {
+                    trace.invalid_genes += 1;
+                    continue;
+                },
+            };
+
+            let (x, y) = position;
+            let next = match facing {
+                Direction::North if y > 0 => Some((x, y - 1)),
+                Direction::East => Some((x + 1, y)),
+                Direction::South => Some((x, y + 1)),
+                Direction::West if x > 0 => Some((x - 1, y)),
+                _ => None,
+            };
+            let next = match next {
+                Some(next) if !self.is_wall(next.0, next.1) => next,
+                Some(next) if next.0 < self.width && next.1 < self.height => {
+                    trace.collision = Some(next);
+                    break;
+                },
+                _ => {
+                    trace.collision = Some(position);
+                    break;
+                },
+            };
+
+            consecutive_steps += 1;
+            if trace.max_consecutive_steps < consecutive_steps {
+                trace.max_consecutive_steps = consecutive_steps;
+            }
+
+            if !visited.insert(next) {
+                trace.revisits.push(next);
+                consecutive_steps = 0;
+            }
+            trace.path.push(next);
+            position = next;
+
+            let distance = self.distance_to_goal(position);
+            if distance < trace.closest_distance {
+                trace.closest_distance = distance;
+            }
+            trace.reached_goal = position == self.goal;
+        }
+
+        trace
+    }
+}
+
+impl Problem<i32> for Maze {
+    // Rewards long runs of fresh steps, plus getting close to the goal for
+    // walks that never hit a wall.
+    fn fitness(&self, genome: &Vec<i32>) -> f32 {
+        let trace = self.walk(genome);
+        let max_steps = genome.len().max(1) as f32;
+        let max_distance = (genome.len() * 2).max(1) as f32;
+
+        let factor_consecutive_steps = trace.max_consecutive_steps as f32 / max_steps;
+        let factor_close = 1.0 - trace.closest_distance as f32 / max_distance;
+
+        if trace.collision.is_some() {
+            factor_consecutive_steps
+        } else {
+            factor_close * 0.2 + factor_consecutive_steps
+        }
+    }
+}
+
+// ASCII legend: '#' wall, 'S' start, 'G' goal, '.' walked once, 'o' walked
+// again, 'X' where the walk hit a wall.
+impl Render<i32> for Maze {
+    fn render_ascii(&self, genome: &Vec<i32>) -> String {
+        let trace = self.walk(genome);
+        let mut canvas: Vec<Vec<char>> = (0..self.height)
+            .map(|y| (0..self.width).map(|x| if self.is_wall(x, y) { '#' } else { ' ' }).collect())
+            .collect();
+
+        for &(x, y) in &trace.path {
+            canvas[y][x] = '.';
+        }
+        for &(x, y) in &trace.revisits {
+            canvas[y][x] = 'o';
+        }
+        canvas[self.start.1][self.start.0] = 'S';
+        canvas[self.goal.1][self.goal.0] = 'G';
+        if let Some((x, y)) = trace.collision {
+            canvas[y][x] = 'X';
+        }
+
+        let mut picture = String::new();
+        for row in canvas {
+            picture.extend(row);
+            picture.push('\n');
+        }
+        picture.push_str(&format!("steps: {}, revisits: {}, collision: {}, goal reached: {}\n",
+                                  trace.path.len() - 1,
+                                  trace.revisits.len(),
+                                  trace.collision.map_or("none".to_string(), |(x, y)| format!("({}, {})", x, y)),
+                                  trace.reached_goal));
+        picture
+    }
+
+    fn render_svg(&self, genome: &Vec<i32>) -> String {
+        let trace = self.walk(genome);
+        let cell = 20;
+        let center = |position: (usize, usize)| (position.0 * cell + cell / 2, position.1 * cell + cell / 2);
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+                              self.width * cell, self.height * cell);
+        svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+                              self.width * cell, self.height * cell));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_wall(x, y) {
+                    svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#505050\"/>\n",
+                                          x * cell, y * cell, cell, cell));
+                }
+            }
+        }
+
+        for &(position, color) in [(self.start, "#2ca02c"), (self.goal, "#0072bd")].iter() {
+            svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                                  position.0 * cell, position.1 * cell, cell, cell, color));
+        }
+
+        let points: Vec<String> = trace.path.iter()
+            .map(|&position| { let (x, y) = center(position); format!("{},{}", x, y) })
+            .collect();
+        svg.push_str(&format!("<polyline points=\"{}\" fill=\"none\" stroke=\"#ff7f0e\" stroke-width=\"4\"/>\n",
+                              points.join(" ")));
+
+        for &position in &trace.revisits {
+            let (x, y) = center(position);
+            svg.push_str(&format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#9467bd\"/>\n", x, y, cell / 4));
+        }
+
+        if let Some(position) = trace.collision {
+            let (x, y) = center(position);
+            let arm = cell / 3;
+            svg.push_str(&format!("<path d=\"M{} {} L{} {} M{} {} L{} {}\" stroke=\"#d62728\" stroke-width=\"4\"/>\n",
+                                  x - arm, y - arm, x + arm, y + arm, x - arm, y + arm, x + arm, y - arm));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Genes 5 are outside the relative encoding and skipped, so they only
+    // stretch the genome the fitness is normalized by.
+    fn padded(moves: &[i32], length: usize) -> Vec<i32> {
+        let mut genome = moves.to_vec();
+        genome.resize(length, 5);
+        genome
+    }
+
+    #[test]
+    fn closest_distance_is_the_minimum_along_the_walk() {
+        let maze = Maze::default_maze();
+        // From S (1, 10) facing south: turn east and walk to (4, 10), 26 away from G
+        let genome = padded(&[0, 1, 1], 40);
+        let trace = maze.walk(&genome);
+        assert_eq!(trace.path, vec![(1, 10), (2, 10), (3, 10), (4, 10)]);
+        assert_eq!(trace.closest_distance, 26);
+        assert_eq!(trace.invalid_genes, 37);
+        assert!(trace.collision.is_none());
+
+        // 0.2 * (1 - 26 / 80) + 3 / 40
+        assert!((maze.fitness(&genome) - 0.21).abs() < 1e-6);
+    }
+
+    #[test]
+    fn collisions_only_score_consecutive_steps() {
+        let maze = Maze::default_maze();
+        // East to (4, 10), then once more into the wall at (5, 10)
+        let genome = padded(&[0, 1, 1, 1], 40);
+        let trace = maze.walk(&genome);
+        assert_eq!(trace.collision, Some((5, 10)));
+        assert!((maze.fitness(&genome) - 3.0 / 40.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn revisits_are_recorded() {
+        let maze = Maze::default_maze().with_encoding(MoveEncoding::AbsoluteDirection);
+        // East, west back onto S, east again
+        let trace = maze.walk(&vec![1, 3, 1]);
+        assert_eq!(trace.revisits, vec![(1, 10), (2, 10)]);
+    }
+}
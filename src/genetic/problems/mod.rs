@@ -0,0 +1,86 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+pub mod benchmarks;
+pub mod binary;
+pub mod coloring;
+pub mod knapsack;
+pub mod maze;
+pub mod pattern;
+pub mod queens;
+pub mod sat;
+pub mod tsp;
+
+// A problem owns whatever data its fitness needs (distance matrices, loaded
+// instances...) so it can't be a plain `fn`. Hand it to a population through a
+// closure: `move |genome, _| problem.fitness(genome)`.
+pub trait Problem<T> {
+    fn fitness(&self, genome: &Vec<T>) -> f32;
+
+    // Best reachable fitness, when it is known.
+    fn optimum(&self) -> Option<f32> {
+        None
+    }
+
+    // Whether the genome satisfies the problem's hard constraints; fitness of an
+    // infeasible genome is repaired or penalized, depending on the problem.
+    fn is_feasible(&self, _genome: &Vec<T>) -> bool {
+        true
+    }
+
+    // The solution the genome stands for, the one its fitness was computed
+    // on: a repaired copy for problems that repair, the genome itself otherwise.
+    fn decode(&self, genome: &Vec<T>) -> Vec<T>
+        where T: Clone
+    {
+        genome.clone()
+    }
+
+    fn is_solved(&self, fitness: f32) -> bool {
+        match self.optimum() {
+            Some(optimum) => optimum - fitness <= 1e-6 * optimum.abs().max(1.0),
+            None => false,
+        }
+    }
+}
+
+// Problem-specific pictures of a genome, for diagnostics and the CLI.
+pub trait Render<T> {
+    fn render_ascii(&self, genome: &Vec<T>) -> String;
+    fn render_svg(&self, genome: &Vec<T>) -> String;
+}
+
+#[derive(Debug)]
+pub enum ProblemError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProblemError::Io(ref error) => write!(f, "I/O error: {}", error),
+            ProblemError::Parse(ref message) => write!(f, "parse error: {}", message),
+        }
+    }
+}
+
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String, ProblemError> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+pub fn parse_number<N: FromStr>(field: &str) -> Result<N, ProblemError> {
+    field.trim().parse::<N>().map_err(|_| ProblemError::Parse(format!("invalid number '{}'", field)))
+}
+
+impl From<io::Error> for ProblemError {
+    fn from(error: io::Error) -> ProblemError {
+        ProblemError::Io(error)
+    }
+}
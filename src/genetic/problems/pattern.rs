@@ -0,0 +1,246 @@
+use std::path::Path;
+
+use genetic::problems::{Problem, ProblemError, Render, read_file, parse_number};
+use genetic::random;
+
+// The pattern `pattern_recognition` has always been matched against.
+const DEFAULT_PATTERN: &'static str = include_str!("../../../data/patterns/default.txt");
+
+// Row-major bitmap, 1 for a set pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub bits: Vec<u8>,
+}
+
+impl Bitmap {
+    // Plain PBM (starting with "P1") or the text format of `parse_text`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Bitmap, ProblemError> {
+        let contents = read_file(path)?;
+        if contents.trim_left().starts_with("P1") {
+            Bitmap::parse_pbm(&contents)
+        } else {
+            Bitmap::parse_text(&contents)
+        }
+    }
+
+    pub fn parse_pbm(contents: &str) -> Result<Bitmap, ProblemError> {
+        let mut tokens = Vec::new();
+        for line in contents.lines() {
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => line,
+            };
+            tokens.extend(line.split_whitespace());
+        }
+
+        if tokens.len() < 3 || tokens[0] != "P1" {
+            return Err(ProblemError::Parse("expected a plain PBM (P1) header".to_string()));
+        }
+        let width: usize = parse_number(tokens[1])?;
+        let height: usize = parse_number(tokens[2])?;
+        if width == 0 || height == 0 {
+            return Err(ProblemError::Parse("pattern is empty".to_string()));
+        }
+
+        // Pixels may be written without separators, e.g. "0110".
+        let mut bits = Vec::with_capacity(width * height);
+        for token in &tokens[3..] {
+            for digit in token.chars() {
+                match digit {
+                    '0' => bits.push(0),
+                    '1' => bits.push(1),
+                    other => return Err(ProblemError::Parse(format!("invalid PBM pixel '{}'", other))),
+                }
+            }
+        }
+
+        if bits.len() != width * height {
+            return Err(ProblemError::Parse(format!("expected {} pixels, found {}", width * height, bits.len())));
+        }
+        Ok(Bitmap { width: width, height: height, bits: bits })
+    }
+
+    // One row per line, '#', 'X' or '1' for set pixels and '.', ' ' or '0' for
+    // clear ones. All rows must have the same width.
+    pub fn parse_text(contents: &str) -> Result<Bitmap, ProblemError> {
+        let rows: Vec<&str> = contents.lines().filter(|line| !line.trim().is_empty()).collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+
+        let mut bits = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            if row.chars().count() != width {
+                return Err(ProblemError::Parse("pattern rows differ in width".to_string()));
+            }
+            for symbol in row.chars() {
+                match symbol {
+                    '#' | 'X' | '1' => bits.push(1),
+                    '.' | ' ' | '0' => bits.push(0),
+                    other => return Err(ProblemError::Parse(format!("unknown pattern symbol '{}'", other))),
+                }
+            }
+        }
+
+        if bits.is_empty() {
+            return Err(ProblemError::Parse("pattern is empty".to_string()));
+        }
+        Ok(Bitmap { width: width, height: rows.len(), bits: bits })
+    }
+
+    pub fn default_pattern() -> Bitmap {
+        Bitmap::parse_text(DEFAULT_PATTERN).expect("default pattern is valid")
+    }
+
+    fn rows(&self, bits: &Vec<u8>) -> Vec<String> {
+        bits.chunks(self.width)
+            .map(|row| row.iter().map(|&bit| if bit == 1 { '#' } else { '.' }).collect())
+            .collect()
+    }
+}
+
+// Evolves a bitmap towards the closest of several targets. With `noise` above
+// zero, each target pixel is flipped with that probability on every
+// evaluation, so fitness becomes a noisy estimate.
+pub struct PatternMatching {
+    pub targets: Vec<Bitmap>,
+    pub noise: f32,
+}
+
+impl PatternMatching {
+    pub fn new(targets: Vec<Bitmap>) -> PatternMatching {
+        assert!(!targets.is_empty(), "pattern matching needs at least one target");
+        let size = targets[0].bits.len();
+        assert!(targets.iter().all(|target| target.bits.len() == size),
+                "all targets must have the same number of pixels");
+        PatternMatching { targets: targets, noise: 0.0 }
+    }
+
+    pub fn with_noise(mut self, noise: f32) -> PatternMatching {
+        self.noise = noise;
+        self
+    }
+
+    pub fn genome_size(&self) -> usize {
+        self.targets[0].bits.len()
+    }
+
+    // Fraction of pixels equal to the target, ignoring noise.
+    pub fn similarity(&self, genome: &Vec<u8>, target: &Bitmap) -> f32 {
+        let matches = genome.iter().zip(target.bits.iter()).filter(|&(a, b)| a == b).count();
+        matches as f32 / target.bits.len() as f32
+    }
+
+    pub fn closest_target(&self, genome: &Vec<u8>) -> &Bitmap {
+        let mut closest = &self.targets[0];
+        for target in &self.targets {
+            if self.similarity(genome, target) > self.similarity(genome, closest) {
+                closest = target;
+            }
+        }
+        closest
+    }
+}
+
+impl Problem<u8> for PatternMatching {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        let mut best: f32 = 0.0;
+        for target in &self.targets {
+            let mut matches = 0;
+            for (gene, pixel) in genome.iter().zip(target.bits.iter()) {
+                let flipped = self.noise > 0.0 && random::random::<f32>() < self.noise;
+                if (gene == pixel) != flipped {
+                    matches += 1;
+                }
+            }
+            best = best.max(matches as f32 / target.bits.len() as f32);
+        }
+        best
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(1.0)
+    }
+}
+
+// Shows the evolved pattern on the left and its closest target on the right.
+impl Render<u8> for PatternMatching {
+    fn render_ascii(&self, genome: &Vec<u8>) -> String {
+        let target = self.closest_target(genome);
+        let mut picture = format!("{:width$}   target\n", "evolved", width = target.width.max(7));
+        for (evolved, wanted) in target.rows(genome).iter().zip(target.rows(&target.bits).iter()) {
+            picture.push_str(&format!("{:width$}   {}\n", evolved, wanted, width = target.width.max(7)));
+        }
+        picture.push_str(&format!("similarity: {:.3}\n", self.similarity(genome, target)));
+        picture
+    }
+
+    fn render_svg(&self, genome: &Vec<u8>) -> String {
+        let target = self.closest_target(genome);
+        let cell = 20;
+        let gap = cell * 2;
+        let panel = target.width * cell;
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+                              panel * 2 + gap, target.height * cell);
+        for (offset, bits) in [(0, genome), (panel + gap, &target.bits)].iter() {
+            for (i, &bit) in bits.iter().enumerate() {
+                let (x, y) = (i % target.width, i / target.width);
+                let mismatch = genome[i] != target.bits[i];
+                let color = match (bit, mismatch) {
+                    (1, false) => "#202020",
+                    (1, true) => "#d62728",
+                    (_, false) => "#ffffff",
+                    (_, true) => "#f4b6b6",
+                };
+                svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#c0c0c0\"/>\n",
+                                      offset + x * cell, y * cell, cell, cell, color));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_bundled_patterns() {
+        let cross = Bitmap::load("data/patterns/cross.pbm").unwrap();
+        assert_eq!((cross.width, cross.height), (6, 6));
+        assert_eq!(&cross.bits[..6], &[0, 0, 1, 1, 0, 0]);
+        assert_eq!(cross.bits.iter().filter(|&&bit| bit == 1).count(), 20);
+
+        let default = Bitmap::load("data/patterns/default.txt").unwrap();
+        assert_eq!(default, Bitmap::default_pattern());
+        assert_eq!((default.width, default.height), (6, 6));
+        assert_eq!(&default.bits[..6], &[0, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn pbm_pixels_may_be_packed_and_commented() {
+        let bitmap = Bitmap::parse_pbm("P1 # packed\n3 2\n010\n1 0 1 # last row\n").unwrap();
+        assert_eq!(bitmap.bits, vec![0, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn pbm_rejects_bad_images() {
+        assert!(Bitmap::parse_pbm("P1\n0 0\n").is_err());
+        assert!(Bitmap::parse_pbm("P1\n0 3\n").is_err());
+        assert!(Bitmap::parse_pbm("P1\n2 2\n0 1 1\n").is_err());
+        assert!(Bitmap::parse_pbm("P1\n1 1\n2\n").is_err());
+        assert!(Bitmap::parse_pbm("P4\n1 1\n0\n").is_err());
+    }
+
+    #[test]
+    fn text_accepts_every_symbol_and_rejects_bad_patterns() {
+        let bitmap = Bitmap::parse_text("#X1\n. 0\n").unwrap();
+        assert_eq!(bitmap.bits, vec![1, 1, 1, 0, 0, 0]);
+
+        assert!(Bitmap::parse_text("").is_err());
+        assert!(Bitmap::parse_text("##\n#\n").is_err());
+        assert!(Bitmap::parse_text("#?\n").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use genetic::helpers::Range;
+use genetic::problems::{Problem, Render};
+
+// N-Queens board: gene `i` is the row of the queen standing in column `i`.
+// Works with permutations (`Population::new_ordered`, no two queens share a
+// row) as well as free integer genomes over `range()`, where row clashes count.
+pub struct NQueens {
+    pub size: usize,
+    solutions: RefCell<HashSet<Vec<i32>>>,
+}
+
+impl NQueens {
+    pub fn new(size: usize) -> NQueens {
+        NQueens {
+            size: size,
+            solutions: RefCell::new(HashSet::new()),
+        }
+    }
+
+    pub fn range(&self) -> Range<i32> {
+        Range::new(0, self.size as i32 - 1)
+    }
+
+    pub fn max_attacking_pairs(&self) -> usize {
+        self.size * self.size.saturating_sub(1) / 2
+    }
+
+    // Number of distinct conflict-free boards seen by `fitness` so far.
+    pub fn distinct_solutions(&self) -> usize {
+        self.solutions.borrow().len()
+    }
+
+    // Every pair of columns whose queens attack each other, row or diagonal.
+    pub fn conflicting_pairs(&self, genome: &Vec<i32>) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for i in 0..genome.len() {
+            for j in (i + 1)..genome.len() {
+                let same_row = genome[i] == genome[j];
+                let same_diagonal = (genome[i] - genome[j]).abs() as usize == j - i;
+                if same_row || same_diagonal {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+// Counts attacking pairs in O(N): k queens sharing a row or a diagonal make
+// k * (k - 1) / 2 pairs.
+pub fn attacking_pairs(genome: &Vec<i32>) -> usize {
+    let mut rows: HashMap<i64, usize> = HashMap::with_capacity(genome.len());
+    let mut diagonals: HashMap<i64, usize> = HashMap::with_capacity(genome.len());
+    let mut anti_diagonals: HashMap<i64, usize> = HashMap::with_capacity(genome.len());
+
+    for (column, row) in genome.iter().enumerate() {
+        let (column, row) = (column as i64, *row as i64);
+        *rows.entry(row).or_insert(0) += 1;
+        *diagonals.entry(row - column).or_insert(0) += 1;
+        *anti_diagonals.entry(row + column).or_insert(0) += 1;
+    }
+
+    rows.values()
+        .chain(diagonals.values())
+        .chain(anti_diagonals.values())
+        .map(|&queens| queens * (queens - 1) / 2)
+        .sum()
+}
+
+impl Problem<i32> for NQueens {
+    fn fitness(&self, genome: &Vec<i32>) -> f32 {
+        let pairs = attacking_pairs(genome);
+        if pairs == 0 {
+            self.solutions.borrow_mut().insert(genome.clone());
+        }
+        1.0 - pairs as f32 / self.max_attacking_pairs().max(1) as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        Some(1.0)
+    }
+}
+
+// ASCII legend: 'Q' safe queen, 'X' queen under attack, '.' empty square.
+impl Render<i32> for NQueens {
+    fn render_ascii(&self, genome: &Vec<i32>) -> String {
+        let pairs = self.conflicting_pairs(genome);
+        let attacked = |column: usize| pairs.iter().any(|&(i, j)| i == column || j == column);
+
+        let mut picture = String::new();
+        for row in 0..self.size as i32 {
+            for column in 0..genome.len() {
+                let symbol = if genome[column] != row {
+                    '.'
+                } else if attacked(column) {
+                    'X'
+                } else {
+                    'Q'
+                };
+                picture.push(symbol);
+                picture.push(' ');
+            }
+            picture.push('\n');
+        }
+
+        picture.push_str(&format!("conflicting pairs: {}\n", pairs.len()));
+        for (i, j) in pairs {
+            picture.push_str(&format!("  column {} (row {}) <-> column {} (row {})\n",
+                                      i, genome[i], j, genome[j]));
+        }
+        picture
+    }
+
+    fn render_svg(&self, genome: &Vec<i32>) -> String {
+        let cell = 40;
+        let side = self.size * cell;
+        // Free queens genomes can hold rows off the board; those queens are not drawn
+        let on_board = |column: usize| column < self.size && genome[column] >= 0 && (genome[column] as usize) < self.size;
+        let center = |column: usize| (column * cell + cell / 2, genome[column] as usize * cell + cell / 2);
+        let pairs: Vec<(usize, usize)> = self.conflicting_pairs(genome).into_iter()
+            .filter(|&(i, j)| on_board(i) && on_board(j))
+            .collect();
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+                              side, side);
+        for row in 0..self.size {
+            for column in 0..self.size {
+                let color = if (row + column) % 2 == 0 { "#f0d9b5" } else { "#b58863" };
+                svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                                      column * cell, row * cell, cell, cell, color));
+            }
+        }
+
+        for &(i, j) in &pairs {
+            let ((x1, y1), (x2, y2)) = (center(i), center(j));
+            svg.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#d62728\" stroke-width=\"3\"/>\n",
+                                  x1, y1, x2, y2));
+        }
+
+        for column in (0..genome.len()).filter(|&column| on_board(column)) {
+            let (x, y) = center(column);
+            let attacked = pairs.iter().any(|&(i, j)| i == column || j == column);
+            let color = if attacked { "#d62728" } else { "#202020" };
+            svg.push_str(&format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n", x, y, cell / 3, color));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use genetic::problems::{Problem, ProblemError, read_file, parse_number};
+
+// MAX-SAT over `Population<u8>`: gene `i` is the value of variable `i + 1`.
+// Fitness is the number of satisfied clauses.
+pub struct MaxSat {
+    pub variables: usize,
+    // DIMACS literals: `v` is variable v, `-v` its negation.
+    pub clauses: Vec<Vec<i32>>,
+}
+
+impl MaxSat {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<MaxSat, ProblemError> {
+        let contents = read_file(path)?;
+        MaxSat::parse(&contents)
+    }
+
+    // DIMACS CNF: 'c' comment lines, a `p cnf VARIABLES CLAUSES` line, then
+    // clauses as literals terminated by 0, free to span lines. A '%' line (as
+    // in SATLIB) ends the formula.
+    pub fn parse(contents: &str) -> Result<MaxSat, ProblemError> {
+        let mut header: Option<(usize, usize)> = None;
+        let mut clauses = Vec::new();
+        let mut clause = Vec::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            if line.starts_with('%') {
+                break;
+            }
+            if line.starts_with('p') {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() != 4 || fields[1] != "cnf" {
+                    return Err(ProblemError::Parse(format!("bad problem line '{}'", line)));
+                }
+                header = Some((parse_number(fields[2])?, parse_number(fields[3])?));
+                continue;
+            }
+
+            for field in line.split_whitespace() {
+                let literal: i32 = parse_number(field)?;
+                if literal == 0 {
+                    clauses.push(clause);
+                    clause = Vec::new();
+                } else {
+                    clause.push(literal);
+                }
+            }
+        }
+        if !clause.is_empty() {
+            clauses.push(clause);
+        }
+
+        let (variables, expected_clauses) = header.ok_or(ProblemError::Parse("missing 'p cnf' line".to_string()))?;
+        if clauses.len() != expected_clauses {
+            return Err(ProblemError::Parse(format!("expected {} clauses, found {}",
+                                                   expected_clauses, clauses.len())));
+        }
+        if clauses.iter().any(|clause| clause.iter().any(|literal| literal.abs() as usize > variables)) {
+            return Err(ProblemError::Parse(format!("literal outside the {} declared variables", variables)));
+        }
+
+        Ok(MaxSat { variables: variables, clauses: clauses })
+    }
+
+    // Literals over variables past the end of a short genome are unsatisfied.
+    pub fn satisfied_clauses(&self, genome: &Vec<u8>) -> usize {
+        self.clauses.iter()
+            .filter(|clause| clause.iter().any(|&literal| {
+                genome.get(literal.abs() as usize - 1)
+                    .map_or(false, |&gene| (gene == 1) == (literal > 0))
+            }))
+            .count()
+    }
+}
+
+impl Problem<u8> for MaxSat {
+    fn fitness(&self, genome: &Vec<u8>) -> f32 {
+        self.satisfied_clauses(genome) as f32
+    }
+
+    // Every clause satisfied; never reached on unsatisfiable formulas.
+    fn optimum(&self) -> Option<f32> {
+        Some(self.clauses.len() as f32)
+    }
+
+    fn is_feasible(&self, genome: &Vec<u8>) -> bool {
+        self.satisfied_clauses(genome) == self.clauses.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn planted_solution_satisfies_planted20() {
+        let formula = MaxSat::load("data/sat/planted20.cnf").unwrap();
+        assert_eq!(formula.variables, 20);
+        assert_eq!(formula.clauses.len(), 85);
+
+        let planted = vec![1, 1, 0, 1, 0, 1, 1, 0, 1, 1, 1, 1, 1, 0, 0, 1, 0, 0, 0, 0];
+        assert_eq!(formula.fitness(&planted), 85.0);
+        assert!(formula.is_solved(formula.fitness(&planted)));
+        assert!(formula.is_feasible(&planted));
+    }
+
+    #[test]
+    fn short_genomes_leave_clauses_unsatisfied() {
+        let formula = MaxSat::parse("p cnf 3 2\n1 0\n-3 0\n").unwrap();
+        assert_eq!(formula.satisfied_clauses(&vec![1]), 1);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_formulas() {
+        assert!(MaxSat::parse("p cnf 2 2\n1 2 0\n").is_err());
+        assert!(MaxSat::parse("p cnf 2 1\n1 3 0\n").is_err());
+        assert!(MaxSat::parse("p sat 2 1\n1 2 0\n").is_err());
+        assert!(MaxSat::parse("1 2 0\n").is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use std::path::Path;
+use std::f64;
+
+use genetic::helpers::is_permutation;
+use genetic::problems::{Problem, ProblemError, read_file, parse_number};
+
+// Traveling Salesman over `Individual<i32>` permutations of 0..dimension, as
+// created by `Population::new_ordered`. Gene `i` is the (0-based) TSPLIB node
+// visited in step `i` and the tour closes back on the first node.
+pub struct Tsp {
+    pub name: String,
+    pub dimension: usize,
+    distances: Vec<i64>,
+    reference_length: i64,
+    optimal_tour: Option<Vec<i32>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EdgeWeightType {
+    Euclidean2d,
+    Ceil2d,
+    Geographical,
+    Att,
+    Explicit,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EdgeWeightFormat {
+    FullMatrix,
+    UpperRow,
+    LowerRow,
+    UpperDiagonalRow,
+    LowerDiagonalRow,
+}
+
+impl Tsp {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Tsp, ProblemError> {
+        let contents = read_file(path)?;
+        Tsp::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Tsp, ProblemError> {
+        let mut name = String::from("unnamed");
+        let mut dimension: Option<usize> = None;
+        let mut weight_type: Option<EdgeWeightType> = None;
+        let mut weight_format = EdgeWeightFormat::FullMatrix;
+        let mut asymmetric = false;
+        let mut coordinates: Vec<(f64, f64)> = Vec::new();
+        let mut weights: Vec<i64> = Vec::new();
+
+        let mut section = "";
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.chars().next().map_or(false, |c| c.is_alphabetic()) {
+                let (key, value) = split_header(line);
+                section = "";
+                match key {
+                    "NAME" => name = value.to_string(),
+                    "TYPE" => {
+                        if value != "TSP" && value != "ATSP" {
+                            return Err(ProblemError::Parse(format!("unsupported problem type {}", value)));
+                        }
+                        asymmetric = value == "ATSP";
+                    },
+                    "DIMENSION" => dimension = Some(parse_number(value)?),
+                    "EDGE_WEIGHT_TYPE" => weight_type = Some(parse_weight_type(value)?),
+                    "EDGE_WEIGHT_FORMAT" if value != "FUNCTION" => weight_format = parse_weight_format(value)?,
+                    "NODE_COORD_SECTION" => section = "coordinates",
+                    "EDGE_WEIGHT_SECTION" => section = "weights",
+                    "DISPLAY_DATA_SECTION" | "FIXED_EDGES_SECTION" => section = "ignored",
+                    "EOF" => break,
+                    _ => (),
+                }
+                continue;
+            }
+
+            match section {
+                "coordinates" => {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    if fields.len() < 3 {
+                        return Err(ProblemError::Parse(format!("bad coordinate line '{}'", line)));
+                    }
+                    coordinates.push((parse_number(fields[1])?, parse_number(fields[2])?));
+                },
+                "weights" => {
+                    for field in line.split_whitespace() {
+                        let weight: f64 = parse_number(field)?;
+                        weights.push(weight as i64);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        let dimension = dimension.ok_or(ProblemError::Parse("missing DIMENSION".to_string()))?;
+        let weight_type = weight_type.ok_or(ProblemError::Parse("missing EDGE_WEIGHT_TYPE".to_string()))?;
+        // Coordinates and the triangular formats can only describe symmetric distances
+        if asymmetric && (weight_type != EdgeWeightType::Explicit || weight_format != EdgeWeightFormat::FullMatrix) {
+            return Err(ProblemError::Parse("ATSP needs EXPLICIT edge weights in a FULL_MATRIX".to_string()));
+        }
+
+        let distances = if weight_type == EdgeWeightType::Explicit {
+            expand_weights(&weights, dimension, weight_format)?
+        } else {
+            if coordinates.len() != dimension {
+                return Err(ProblemError::Parse(format!("expected {} coordinates, found {}",
+                                                       dimension, coordinates.len())));
+            }
+            let mut distances = vec![0; dimension * dimension];
+            for i in 0..dimension {
+                for j in 0..dimension {
+                    if i != j {
+                        distances[i * dimension + j] = coordinate_distance(weight_type,
+                                                                           coordinates[i],
+                                                                           coordinates[j]);
+                    }
+                }
+            }
+            distances
+        };
+
+        Ok(Tsp::from_distances(name, dimension, distances))
+    }
+
+    pub fn from_matrix(name: &str, matrix: &Vec<Vec<i64>>) -> Result<Tsp, ProblemError> {
+        let dimension = matrix.len();
+        let mut distances = Vec::with_capacity(dimension * dimension);
+        for row in matrix {
+            if row.len() != dimension {
+                return Err(ProblemError::Parse(format!("distance matrix must be square, found a row of {} in {}",
+                                                       row.len(), dimension)));
+            }
+            distances.extend_from_slice(row);
+        }
+        Ok(Tsp::from_distances(name.to_string(), dimension, distances))
+    }
+
+    fn from_distances(name: String, dimension: usize, distances: Vec<i64>) -> Tsp {
+        let mut tsp = Tsp {
+            name: name,
+            dimension: dimension,
+            distances: distances,
+            reference_length: 1,
+            optimal_tour: None,
+        };
+        tsp.reference_length = tsp.nearest_neighbour_length().max(1);
+        tsp
+    }
+
+    pub fn distance(&self, from: usize, to: usize) -> i64 {
+        self.distances[from * self.dimension + to]
+    }
+
+    pub fn tour_length(&self, tour: &Vec<i32>) -> i64 {
+        let mut length = 0;
+        for i in 0..tour.len() {
+            let next = (i + 1) % tour.len();
+            length += self.distance(tour[i] as usize, tour[next] as usize);
+        }
+        length
+    }
+
+    // Reads a TSPLIB `.opt.tour` file. Load it before evolving: from then on
+    // fitness is measured against the optimum, so 1.0 means an optimal tour.
+    pub fn load_optimal_tour<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ProblemError> {
+        let contents = read_file(path)?;
+
+        let mut tour = Vec::new();
+        let mut in_tour = false;
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with("TOUR_SECTION") {
+                in_tour = true;
+                continue;
+            }
+            if !in_tour || line.is_empty() {
+                continue;
+            }
+            if line.starts_with("EOF") {
+                break;
+            }
+            for field in line.split_whitespace() {
+                let node: i64 = parse_number(field)?;
+                if node == -1 {
+                    in_tour = false;
+                    break;
+                }
+                tour.push(node as i32 - 1);
+            }
+        }
+
+        self.set_optimal_tour(tour)
+    }
+
+    pub fn set_optimal_tour(&mut self, tour: Vec<i32>) -> Result<(), ProblemError> {
+        if tour.len() != self.dimension || !is_permutation(&tour) {
+            return Err(ProblemError::Parse(format!("optimal tour is not a permutation of {} nodes",
+                                                   self.dimension)));
+        }
+        self.reference_length = self.tour_length(&tour).max(1);
+        self.optimal_tour = Some(tour);
+        Ok(())
+    }
+
+    pub fn optimal_tour(&self) -> Option<&Vec<i32>> {
+        self.optimal_tour.as_ref()
+    }
+
+    pub fn optimal_length(&self) -> Option<i64> {
+        self.optimal_tour.as_ref().map(|tour| self.tour_length(tour))
+    }
+
+    // Percentage above the known optimum, e.g. 5.0 for a tour 5% too long.
+    pub fn gap(&self, tour: &Vec<i32>) -> Option<f32> {
+        self.optimal_length().map(|optimal| {
+            let length = self.tour_length(tour);
+            100.0 * (length - optimal) as f32 / optimal as f32
+        })
+    }
+
+    fn nearest_neighbour_length(&self) -> i64 {
+        if self.dimension == 0 {
+            return 0;
+        }
+        let mut visited = vec![false; self.dimension];
+        let mut tour = vec![0];
+        visited[0] = true;
+        for _ in 1..self.dimension {
+            let current = *tour.last().unwrap() as usize;
+            let mut nearest = None;
+            for candidate in 0..self.dimension {
+                if visited[candidate] {
+                    continue;
+                }
+                match nearest {
+                    Some(best) if self.distance(current, best) <= self.distance(current, candidate) => (),
+                    _ => nearest = Some(candidate),
+                }
+            }
+            let nearest = nearest.unwrap();
+            visited[nearest] = true;
+            tour.push(nearest as i32);
+        }
+        self.tour_length(&tour)
+    }
+}
+
+impl Problem<i32> for Tsp {
+    // Reference length (optimum if loaded, nearest neighbour tour otherwise)
+    // over tour length, so shorter tours are fitter.
+    fn fitness(&self, genome: &Vec<i32>) -> f32 {
+        if genome.len() != self.dimension || !is_permutation(genome) {
+            return 0.0;
+        }
+        self.reference_length as f32 / self.tour_length(genome).max(1) as f32
+    }
+
+    fn optimum(&self) -> Option<f32> {
+        self.optimal_tour.as_ref().map(|_| 1.0)
+    }
+}
+
+fn split_header(line: &str) -> (&str, &str) {
+    match line.find(':') {
+        Some(index) => (line[..index].trim(), line[index + 1..].trim()),
+        None => (line, ""),
+    }
+}
+
+fn parse_weight_type(value: &str) -> Result<EdgeWeightType, ProblemError> {
+    match value {
+        "EUC_2D" => Ok(EdgeWeightType::Euclidean2d),
+        "CEIL_2D" => Ok(EdgeWeightType::Ceil2d),
+        "GEO" => Ok(EdgeWeightType::Geographical),
+        "ATT" => Ok(EdgeWeightType::Att),
+        "EXPLICIT" => Ok(EdgeWeightType::Explicit),
+        other => Err(ProblemError::Parse(format!("unsupported EDGE_WEIGHT_TYPE {}", other))),
+    }
+}
+
+// Column-wise formats hold the same numbers as the transposed row-wise ones.
+fn parse_weight_format(value: &str) -> Result<EdgeWeightFormat, ProblemError> {
+    match value {
+        "FULL_MATRIX" => Ok(EdgeWeightFormat::FullMatrix),
+        "UPPER_ROW" | "LOWER_COL" => Ok(EdgeWeightFormat::UpperRow),
+        "LOWER_ROW" | "UPPER_COL" => Ok(EdgeWeightFormat::LowerRow),
+        "UPPER_DIAG_ROW" | "LOWER_DIAG_COL" => Ok(EdgeWeightFormat::UpperDiagonalRow),
+        "LOWER_DIAG_ROW" | "UPPER_DIAG_COL" => Ok(EdgeWeightFormat::LowerDiagonalRow),
+        other => Err(ProblemError::Parse(format!("unsupported EDGE_WEIGHT_FORMAT {}", other))),
+    }
+}
+
+fn expand_weights(weights: &Vec<i64>,
+                  dimension: usize,
+                  format: EdgeWeightFormat) -> Result<Vec<i64>, ProblemError> {
+    let mut cells: Vec<(usize, usize)> = Vec::new();
+    for i in 0..dimension {
+        for j in 0..dimension {
+            let included = match format {
+                EdgeWeightFormat::FullMatrix => true,
+                EdgeWeightFormat::UpperRow => j > i,
+                EdgeWeightFormat::LowerRow => j < i,
+                EdgeWeightFormat::UpperDiagonalRow => j >= i,
+                EdgeWeightFormat::LowerDiagonalRow => j <= i,
+            };
+            if included {
+                cells.push((i, j));
+            }
+        }
+    }
+
+    if weights.len() != cells.len() {
+        return Err(ProblemError::Parse(format!("expected {} edge weights, found {}",
+                                               cells.len(), weights.len())));
+    }
+
+    let mut distances = vec![0; dimension * dimension];
+    for (&(i, j), &weight) in cells.iter().zip(weights.iter()) {
+        distances[i * dimension + j] = weight;
+        if format != EdgeWeightFormat::FullMatrix {
+            distances[j * dimension + i] = weight;
+        }
+    }
+    Ok(distances)
+}
+
+// Distance functions as specified by TSPLIB 95.
+fn coordinate_distance(weight_type: EdgeWeightType, a: (f64, f64), b: (f64, f64)) -> i64 {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    match weight_type {
+        EdgeWeightType::Euclidean2d => (dx * dx + dy * dy).sqrt().round() as i64,
+        EdgeWeightType::Ceil2d => (dx * dx + dy * dy).sqrt().ceil() as i64,
+        EdgeWeightType::Att => {
+            let r = ((dx * dx + dy * dy) / 10.0).sqrt();
+            let t = r.round();
+            if t < r { t as i64 + 1 } else { t as i64 }
+        },
+        EdgeWeightType::Geographical => {
+            let radius = 6378.388;
+            let (latitude_a, longitude_a) = (geo_radians(a.0), geo_radians(a.1));
+            let (latitude_b, longitude_b) = (geo_radians(b.0), geo_radians(b.1));
+            let q1 = (longitude_a - longitude_b).cos();
+            let q2 = (latitude_a - latitude_b).cos();
+            let q3 = (latitude_a + latitude_b).cos();
+            (radius * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0) as i64
+        },
+        EdgeWeightType::Explicit => unreachable!(),
+    }
+}
+
+// TSPLIB encodes GEO coordinates as DDD.MM (degrees and minutes).
+fn geo_radians(coordinate: f64) -> f64 {
+    let pi = 3.141592;
+    let degrees = coordinate.trunc();
+    let minutes = coordinate - degrees;
+    pi * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explicit(format: &str, weights: &str) -> Tsp {
+        let contents = format!("NAME: square\nTYPE: TSP\nDIMENSION: 4\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+                                EDGE_WEIGHT_FORMAT: {}\nEDGE_WEIGHT_SECTION\n{}\nEOF\n", format, weights);
+        Tsp::parse(&contents).unwrap_or_else(|error| panic!("{}: {}", format, error))
+    }
+
+    #[test]
+    fn burma14_optimal_tour_is_3323_long() {
+        let mut tsp = Tsp::load("data/tsp/burma14.tsp").unwrap();
+        assert_eq!(tsp.dimension, 14);
+        tsp.load_optimal_tour("data/tsp/burma14.opt.tour").unwrap();
+
+        let tour = tsp.optimal_tour().unwrap().clone();
+        assert_eq!(tsp.optimal_length(), Some(3323));
+        assert_eq!(tsp.gap(&tour), Some(0.0));
+        assert!(tsp.is_solved(tsp.fitness(&tour)));
+    }
+
+    #[test]
+    fn explicit_formats_describe_the_same_matrix() {
+        let matrix = vec![vec![0, 1, 2, 3],
+                          vec![1, 0, 4, 5],
+                          vec![2, 4, 0, 6],
+                          vec![3, 5, 6, 0]];
+        let formats = [("FULL_MATRIX", "0 1 2 3 1 0 4 5 2 4 0 6 3 5 6 0"),
+                       ("UPPER_ROW", "1 2 3 4 5 6"),
+                       ("LOWER_COL", "1 2 3 4 5 6"),
+                       ("LOWER_ROW", "1 2 4 3 5 6"),
+                       ("UPPER_COL", "1 2 4 3 5 6"),
+                       ("UPPER_DIAG_ROW", "0 1 2 3 0 4 5 0 6 0"),
+                       ("LOWER_DIAG_COL", "0 1 2 3 0 4 5 0 6 0"),
+                       ("LOWER_DIAG_ROW", "0 1 0 2 4 0 3 5 6 0"),
+                       ("UPPER_DIAG_COL", "0 1 0 2 4 0 3 5 6 0")];
+        for &(format, weights) in formats.iter() {
+            let tsp = explicit(format, weights);
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert_eq!(tsp.distance(i, j), matrix[i][j], "{} ({}, {})", format, i, j);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn explicit_weights_must_fill_the_format() {
+        let contents = "TYPE: TSP\nDIMENSION: 4\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\n\
+                        EDGE_WEIGHT_SECTION\n1 2 3 4 5\nEOF\n";
+        assert!(Tsp::parse(contents).is_err());
+    }
+
+    #[test]
+    fn atsp_rejects_coordinates() {
+        let contents = "TYPE: ATSP\nDIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 3 4\nEOF\n";
+        assert!(Tsp::parse(contents).is_err());
+    }
+
+    #[test]
+    fn from_matrix_rejects_ragged_rows() {
+        assert!(Tsp::from_matrix("ragged", &vec![vec![0, 1], vec![1]]).is_err());
+        let tsp = Tsp::from_matrix("pair", &vec![vec![0, 7], vec![7, 0]]).unwrap();
+        assert_eq!(tsp.tour_length(&vec![0, 1]), 14);
+    }
+}
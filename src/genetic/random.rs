@@ -0,0 +1,36 @@
+extern crate rand;
+use self::rand::{Rand, Rng, SeedableRng, StdRng};
+
+use std::cell::RefCell;
+
+// Every random choice in `genetic` draws from this thread-local generator,
+// drop-in for `rand::thread_rng` and `rand::random`. Each thread starts from
+// OS entropy; `seed` makes whatever runs next on the thread reproducible,
+// which is how the experiment runner gives every trial its own seed.
+thread_local!(static GENERATOR: RefCell<StdRng> = RefCell::new(StdRng::new().expect("no OS entropy to seed from")));
+
+pub fn seed(seed: usize) {
+    GENERATOR.with(|generator| *generator.borrow_mut() = SeedableRng::from_seed(&[seed][..]));
+}
+
+// Handle on the current thread's generator.
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadRng;
+
+impl Rng for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        GENERATOR.with(|generator| generator.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        GENERATOR.with(|generator| generator.borrow_mut().next_u64())
+    }
+}
+
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng
+}
+
+pub fn random<T: Rand>() -> T {
+    ThreadRng.gen()
+}
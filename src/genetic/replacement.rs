@@ -0,0 +1,134 @@
+// How offspring make it into the population.
+//  - Generational: a whole new population is bred every generation, possibly
+//    only partially kept (generation gap) or placed by crowding.
+//  - SteadyState: every step breeds `offspring_per_step` (1 or 2) children
+//    from two tournament-selected parents, evaluates them and immediately puts
+//    each one in place of the individual picked by `policy`. One call to
+//    `Population::iterate_generation` runs as many steps as it takes to breed
+//    one population worth of offspring, so both modes spend the same number
+//    of evaluations per generation. Steady-state selection works on raw
+//    fitness: sharing, scaling and the generation gap are not applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplacementMode {
+    Generational,
+    SteadyState { offspring_per_step: usize, policy: ReplacementPolicy },
+}
+
+// Which individual a steady-state child replaces.
+//  - Worst: the least fit individual.
+//  - Random: any individual, uniformly.
+//  - Oldest: the individual born the longest ago.
+//  - MostSimilar: the closest individual under the diversity function.
+//  - Parent: the parent the child was bred from, when the child is at least as fit.
+// With elitism on, the fittest individual is only replaced by a fitter child.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplacementPolicy {
+    Worst,
+    Random,
+    Oldest,
+    MostSimilar,
+    Parent,
+}
+
+// How many of the fittest individuals of a generation are carried over into
+// the next one. Elites replace the worst offspring they are fitter than, after
+// crowding and the generation gap have placed the offspring, so an elite
+// never overwrites a parent that survived.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Elitism {
+    Count(usize),
+    // Fraction of the population size, rounded up.
+    Fraction(f32),
+}
+
+impl Elitism {
+    pub fn count(&self, population_size: usize) -> usize {
+        let count = match *self {
+            Elitism::Count(count) => count,
+            Elitism::Fraction(fraction) => (fraction.max(0.0) * population_size as f32).ceil() as usize,
+        };
+        count.min(population_size)
+    }
+}
+
+// How parents are picked for breeding.
+//  - Tournament: the fittest of `size` random individuals, the population's
+//    default with a size of 4. Only the order of the fitnesses matters, so
+//    fitness scaling changes nothing but ties.
+//  - Roulette: fitness-proportional, so the scaling decides the selection
+//    pressure. Negative fitness counts as zero.
+// Both work on the scaled and shared fitness in generational runs, and on raw
+// fitness in steady-state ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParentSelection {
+    Tournament(usize),
+    Roulette,
+}
+
+// Who survives a generational step. `offspring` is the number of children
+// bred each generation (lambda); the population size (mu) never changes.
+//  - Replace: children take the parents' places, through the generation gap
+//    and crowding when those are on. `offspring` is always mu.
+//  - Plus: the best mu of parents and children together, (mu + lambda).
+//  - Comma: the best mu of the children alone, (mu, lambda); needs lambda >= mu.
+// Plus and Comma rank by raw fitness and ignore the generation gap and
+// crowding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SurvivorSelection {
+    Replace,
+    Plus { offspring: usize },
+    Comma { offspring: usize },
+}
+
+// Fraction of the population replaced by offspring each generation when
+// `has_generation_gap` is on; without a gap the offspring replace everyone.
+//  - Progressive: the schedule the population has always used, growing in
+//    tenths with progress, `ceil(10 * progress) / 10`, and switched off (full
+//    replacement) for the last 10% of the run.
+//  - Fixed: the same fraction every generation.
+//  - Schedule: `(progress, fraction)` points sorted by progress; each fraction
+//    holds from its progress until the next point, and the gap is off before
+//    the first one.
+//  - Adaptive: moves between `min` and `max` with the population diversity
+//    relative to the first generation, so a converging population replaces
+//    fewer individuals and keeps more of what variety it has left.
+//
+// The gap picks that fraction of distinct random slots and puts the offspring
+// bred for each slot there. With crowding (`crowding_factor > 1`), each of
+// those offspring replaces the most similar of `crowding_factor` random
+// individuals instead of its slot, so fewer distinct individuals may be
+// replaced than the fraction says.
+// Crowding still stops for the last 10% of the run. Elites are put back after
+// the gap has been applied, over the worst offspring, so a gap never loses
+// them. (mu + lambda), (mu, lambda) and steady-state runs ignore the gap.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationGap {
+    Progressive,
+    Fixed(f32),
+    Schedule(Vec<(f32, f32)>),
+    Adaptive { min: f32, max: f32 },
+}
+
+impl GenerationGap {
+    // Fraction to replace at `progress` (0 to 1 over the run), or None for
+    // full replacement. `relative_diversity` is the current diversity over
+    // that of the first generation.
+    pub fn fraction(&self, progress: f32, relative_diversity: f32) -> Option<f32> {
+        let fraction = match *self {
+            GenerationGap::Progressive if progress < 0.9 => (10.0 * progress).ceil() / 10.0,
+            GenerationGap::Progressive => return None,
+            GenerationGap::Fixed(fraction) => fraction,
+            GenerationGap::Schedule(ref points) => {
+                match points.iter().take_while(|&&(start, _)| start <= progress).last() {
+                    Some(&(_, fraction)) => fraction,
+                    None => return None,
+                }
+            },
+            GenerationGap::Adaptive { min, max } => {
+                let diversity = if relative_diversity.is_finite() { relative_diversity.max(0.0).min(1.0) } else { 1.0 };
+                min + (max - min) * diversity
+            },
+        };
+        Some(fraction.max(0.0).min(1.0))
+    }
+}
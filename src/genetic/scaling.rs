@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+use std::f32;
+
+// Rescales raw fitness before selection, see `Population::set_scaling`.
+// Implementations leave every value finite and non-negative, and leave
+// degenerate populations (all fitnesses equal) unscaled. Every scaling here
+// keeps the order of the fitnesses, so a tournament only sees the ties it
+// makes; pair it with `ParentSelection::Roulette` to set selection pressure.
+pub trait FitnessScaling {
+    // `progress` runs from 0 at the first generation to 1 at the last.
+    fn scale(&mut self, fitnesses: &mut Vec<f32>, progress: f32);
+}
+
+// Goldberg's linear scaling: keeps the average and maps the best to `c` times
+// the average, lowering `c` when that would push the worst below zero. `c`
+// moves geometrically from `c_start` to `c_end` over the run.
+pub struct LinearScaling {
+    pub c_start: f32,
+    pub c_end: f32,
+}
+
+impl Default for LinearScaling {
+    // The schedule the population has always used.
+    fn default() -> LinearScaling {
+        LinearScaling { c_start: 1.2, c_end: 2.0 }
+    }
+}
+
+impl FitnessScaling for LinearScaling {
+    fn scale(&mut self, fitnesses: &mut Vec<f32>, progress: f32) {
+        sanitize(fitnesses);
+        let (min, average, max) = statistics(fitnesses);
+        if max - average <= f32::EPSILON * max.abs().max(1.0) {
+            return;
+        }
+
+        let c = self.c_start * (self.c_end / self.c_start).powf(progress);
+        let decider = (c * average - max) / (c - 1.0);
+
+        let (alpha, beta) = if min > decider {
+            ((average * (c - 1.0)) / (max - average),
+             (average * (max - c * average)) / (max - average))
+        } else {
+            (average / (average - min),
+             (-min * average) / (average - min))
+        };
+
+        for fitness in fitnesses.iter_mut() {
+            *fitness = alpha * *fitness + beta;
+        }
+        sanitize(fitnesses);
+    }
+}
+
+// Sigma truncation: subtracts `average - c * standard deviation`, so
+// individuals more than `c` deviations below the average get nothing.
+pub struct SigmaTruncation {
+    pub c: f32,
+}
+
+impl FitnessScaling for SigmaTruncation {
+    fn scale(&mut self, fitnesses: &mut Vec<f32>, _: f32) {
+        sanitize(fitnesses);
+        let (_, average, _) = statistics(fitnesses);
+        let variance = fitnesses.iter().map(|fitness| (fitness - average).powi(2)).sum::<f32>() /
+                       fitnesses.len() as f32;
+        if variance <= 0.0 {
+            return;
+        }
+
+        let offset = average - self.c * variance.sqrt();
+        for fitness in fitnesses.iter_mut() {
+            *fitness -= offset;
+        }
+        sanitize(fitnesses);
+    }
+}
+
+// Power law scaling, f^k. `k` slightly above 1 sharpens selection.
+pub struct PowerLaw {
+    pub k: f32,
+}
+
+impl FitnessScaling for PowerLaw {
+    fn scale(&mut self, fitnesses: &mut Vec<f32>, _: f32) {
+        sanitize(fitnesses);
+        for fitness in fitnesses.iter_mut() {
+            *fitness = fitness.powf(self.k);
+        }
+        sanitize(fitnesses);
+    }
+}
+
+// Linear ranking: the worst individual gets `2 - pressure`, the best gets
+// `pressure`, with `pressure` in [1, 2]. Ties share their average rank.
+pub struct RankScaling {
+    pub pressure: f32,
+}
+
+impl FitnessScaling for RankScaling {
+    fn scale(&mut self, fitnesses: &mut Vec<f32>, _: f32) {
+        sanitize(fitnesses);
+        let size = fitnesses.len();
+        if size < 2 {
+            return;
+        }
+
+        let mut order: Vec<usize> = (0..size).collect();
+        order.sort_by(|&a, &b| fitnesses[a].partial_cmp(&fitnesses[b]).unwrap());
+
+        let mut ranks = vec![0.0; size];
+        let mut first = 0;
+        while first < size {
+            let mut last = first;
+            while last + 1 < size && fitnesses[order[last + 1]] == fitnesses[order[first]] {
+                last += 1;
+            }
+            let rank = (first + last) as f32 / 2.0;
+            for &i in &order[first..last + 1] {
+                ranks[i] = rank;
+            }
+            first = last + 1;
+        }
+
+        let pressure = self.pressure.max(1.0).min(2.0);
+        for i in 0..size {
+            fitnesses[i] = 2.0 - pressure + 2.0 * (pressure - 1.0) * ranks[i] / (size - 1) as f32;
+        }
+    }
+}
+
+// Windowing: subtracts the worst fitness seen over the last `window`
+// generations, so selection keeps working once the whole population has
+// climbed far above zero.
+pub struct Windowing {
+    pub window: usize,
+    minimums: VecDeque<f32>,
+}
+
+impl Windowing {
+    pub fn new(window: usize) -> Windowing {
+        Windowing { window: window.max(1), minimums: VecDeque::new() }
+    }
+}
+
+impl FitnessScaling for Windowing {
+    fn scale(&mut self, fitnesses: &mut Vec<f32>, _: f32) {
+        sanitize(fitnesses);
+        let (min, _, max) = statistics(fitnesses);
+        self.minimums.push_back(min);
+        while self.minimums.len() > self.window {
+            self.minimums.pop_front();
+        }
+        if max - min <= f32::EPSILON * max.abs().max(1.0) {
+            return;
+        }
+
+        let baseline = self.minimums.iter().fold(f32::INFINITY, |baseline, &minimum| baseline.min(minimum));
+        for fitness in fitnesses.iter_mut() {
+            *fitness -= baseline;
+        }
+    }
+}
+
+// Replaces NaN, infinite and negative fitness by zero.
+fn sanitize(fitnesses: &mut Vec<f32>) {
+    for fitness in fitnesses.iter_mut() {
+        if !fitness.is_finite() || *fitness < 0.0 {
+            *fitness = 0.0;
+        }
+    }
+}
+
+// (min, average, max)
+fn statistics(fitnesses: &Vec<f32>) -> (f32, f32, f32) {
+    let min = fitnesses.iter().fold(f32::INFINITY, |min, &fitness| min.min(fitness));
+    let max = fitnesses.iter().fold(f32::NEG_INFINITY, |max, &fitness| max.max(fitness));
+    let average = fitnesses.iter().sum::<f32>() / fitnesses.len() as f32;
+    (min, average, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &Vec<f32>, expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn linear_scaling_keeps_the_average_and_the_worst_at_zero() {
+        // c * average - max over c - 1 is 6.8, above the worst fitness, so
+        // the worst maps to 0 rather than the best to c times the average.
+        let mut fitnesses = vec![2.0, 10.0, 10.0, 10.0, 10.0];
+        LinearScaling { c_start: 2.0, c_end: 2.0 }.scale(&mut fitnesses, 0.0);
+        assert_close(&fitnesses, &[0.0, 10.5, 10.5, 10.5, 10.5]);
+
+        let mut fitnesses = vec![1.0, 2.0, 3.0, 4.0, 10.0];
+        LinearScaling { c_start: 1.2, c_end: 2.0 }.scale(&mut fitnesses, 0.0);
+        assert_close(&fitnesses, &[3.6, 3.7333, 3.8667, 4.0, 4.8]);
+    }
+
+    #[test]
+    fn degenerate_populations_are_left_unscaled() {
+        let mut scalings: Vec<Box<FitnessScaling>> = vec![Box::new(LinearScaling::default()),
+                                                          Box::new(SigmaTruncation { c: 2.0 }),
+                                                          Box::new(Windowing::new(5))];
+        for scaling in scalings.iter_mut() {
+            let mut fitnesses = vec![3.0; 4];
+            scaling.scale(&mut fitnesses, 0.5);
+            assert_eq!(fitnesses, vec![3.0; 4]);
+        }
+    }
+
+    #[test]
+    fn windowing_subtracts_the_worst_fitness_of_the_window() {
+        let mut windowing = Windowing::new(2);
+        let mut fitnesses = vec![1.0, 4.0];
+        windowing.scale(&mut fitnesses, 0.0);
+        assert_eq!(fitnesses, vec![0.0, 3.0]);
+
+        let mut fitnesses = vec![3.0, 5.0];
+        windowing.scale(&mut fitnesses, 0.0);
+        assert_eq!(fitnesses, vec![2.0, 4.0]);
+
+        let mut fitnesses = vec![4.0, 5.0];
+        windowing.scale(&mut fitnesses, 0.0);
+        assert_eq!(fitnesses, vec![1.0, 2.0]);
+    }
+}
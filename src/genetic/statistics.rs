@@ -0,0 +1,452 @@
+use std::cmp::Ordering;
+
+use genetic::experiment::quantile;
+
+// Non-parametric tests for comparing runs of different configurations. Every
+// p-value is two-sided and comes from the large-sample approximation, which
+// is fine from about 10 trials per configuration on.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    pub statistic: f32,
+    pub p_value: f32,
+}
+
+// Post-hoc comparison of groups `a` and `b`; `p_value` is already adjusted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairwiseResult {
+    pub a: usize,
+    pub b: usize,
+    pub z: f32,
+    pub p_value: f32,
+}
+
+// Vargha-Delaney thresholds on |A12 - 0.5|.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Magnitude {
+    Negligible,
+    Small,
+    Medium,
+    Large,
+}
+
+// 1-based ranks of `values`, ties sharing their average rank, plus the tie
+// correction term sum(t^3 - t) over every group of t tied values.
+pub fn ranks(values: &[f32]) -> (Vec<f32>, f64) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(Ordering::Equal));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let average_rank = (start + end + 1) as f32 / 2.0;
+        for &index in &order[start..end] {
+            ranks[index] = average_rank;
+        }
+        let tied = (end - start) as f64;
+        ties += tied * tied * tied - tied;
+        start = end;
+    }
+    (ranks, ties)
+}
+
+// Mann-Whitney U test for two independent samples. The statistic is U of `a`;
+// it is large when `a` tends to hold the larger values.
+pub fn mann_whitney_u(a: &[f32], b: &[f32]) -> TestResult {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let combined: Vec<f32> = a.iter().chain(b.iter()).cloned().collect();
+    let (ranks, ties) = ranks(&combined);
+
+    let rank_sum: f64 = ranks[..a.len()].iter().map(|&rank| rank as f64).sum();
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+
+    TestResult { statistic: u as f32, p_value: two_sided_p(u - mean, variance) }
+}
+
+// Wilcoxon signed-rank test for paired samples, trial `i` of `a` against
+// trial `i` of `b`. Equal pairs are dropped. The statistic is W+, the rank
+// sum of the pairs where `a` is larger.
+pub fn wilcoxon_signed_rank(a: &[f32], b: &[f32]) -> TestResult {
+    assert_eq!(a.len(), b.len(), "the signed-rank test needs paired samples");
+    let differences: Vec<f32> = a.iter().zip(b)
+        .filter(|&(x, y)| x != y)
+        .map(|(x, y)| x - y)
+        .collect();
+    let n = differences.len() as f64;
+    if differences.is_empty() {
+        return TestResult { statistic: 0.0, p_value: 1.0 };
+    }
+
+    let magnitudes: Vec<f32> = differences.iter().map(|difference| difference.abs()).collect();
+    let (ranks, ties) = ranks(&magnitudes);
+    let positive: f64 = differences.iter().zip(&ranks)
+        .filter(|&(&difference, _)| difference > 0.0)
+        .fold(0.0, |sum, (_, &rank)| sum + rank as f64);
+    let mean = n * (n + 1.0) / 4.0;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - ties / 48.0;
+
+    TestResult { statistic: positive as f32, p_value: two_sided_p(positive - mean, variance) }
+}
+
+// Kruskal-Wallis H test for two or more independent samples, with the
+// chi-squared approximation on `groups.len() - 1` degrees of freedom.
+pub fn kruskal_wallis(groups: &[Vec<f32>]) -> TestResult {
+    let combined: Vec<f32> = groups.iter().flat_map(|group| group.iter().cloned()).collect();
+    let n = combined.len() as f64;
+    let (ranks, ties) = ranks(&combined);
+
+    let mut sum = 0.0;
+    let mut offset = 0;
+    for group in groups {
+        let rank_sum: f64 = ranks[offset..offset + group.len()].iter().map(|&rank| rank as f64).sum();
+        sum += rank_sum * rank_sum / group.len() as f64;
+        offset += group.len();
+    }
+    let correction = 1.0 - ties / (n * n * n - n);
+    if correction <= 0.0 {
+        return TestResult { statistic: 0.0, p_value: 1.0 };
+    }
+    let h = (12.0 / (n * (n + 1.0)) * sum - 3.0 * (n + 1.0)) / correction;
+    let degrees = (groups.len() - 1) as f64;
+
+    TestResult { statistic: h as f32, p_value: upper_regularized_gamma(degrees / 2.0, h / 2.0) as f32 }
+}
+
+// Dunn's post-hoc test after Kruskal-Wallis, every pair of groups, with
+// Holm's step-down adjustment of the p-values. Positive `z` means group `a`
+// ranks higher than group `b`.
+pub fn dunn(groups: &[Vec<f32>]) -> Vec<PairwiseResult> {
+    let combined: Vec<f32> = groups.iter().flat_map(|group| group.iter().cloned()).collect();
+    let n = combined.len() as f64;
+    let (ranks, ties) = ranks(&combined);
+
+    let mut mean_ranks = Vec::with_capacity(groups.len());
+    let mut offset = 0;
+    for group in groups {
+        let rank_sum: f64 = ranks[offset..offset + group.len()].iter().map(|&rank| rank as f64).sum();
+        mean_ranks.push(rank_sum / group.len() as f64);
+        offset += group.len();
+    }
+
+    let spread = n * (n + 1.0) / 12.0 - ties / (12.0 * (n - 1.0));
+    let mut results = Vec::new();
+    for a in 0..groups.len() {
+        for b in a + 1..groups.len() {
+            let variance = spread * (1.0 / groups[a].len() as f64 + 1.0 / groups[b].len() as f64);
+            let difference = mean_ranks[a] - mean_ranks[b];
+            let z = if variance > 0.0 { difference / variance.sqrt() } else { 0.0 };
+            results.push(PairwiseResult { a: a, b: b, z: z as f32, p_value: two_sided_p(difference, variance) });
+        }
+    }
+
+    let adjusted = holm(&results.iter().map(|result| result.p_value).collect::<Vec<f32>>());
+    for (result, p_value) in results.iter_mut().zip(adjusted) {
+        result.p_value = p_value;
+    }
+    results
+}
+
+// Holm-Bonferroni adjusted p-values, in the order given.
+pub fn holm(p_values: &[f32]) -> Vec<f32> {
+    let mut order: Vec<usize> = (0..p_values.len()).collect();
+    order.sort_by(|&a, &b| p_values[a].partial_cmp(&p_values[b]).unwrap_or(Ordering::Equal));
+
+    let mut adjusted = vec![0.0; p_values.len()];
+    let mut running_max: f32 = 0.0;
+    for (step, &index) in order.iter().enumerate() {
+        let p_value = (p_values[index] * (p_values.len() - step) as f32).min(1.0);
+        running_max = running_max.max(p_value);
+        adjusted[index] = running_max;
+    }
+    adjusted
+}
+
+// Vargha-Delaney A12: the probability that a value drawn from `a` is larger
+// than one drawn from `b`, counting ties as half.
+pub fn a12(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.5;
+    }
+    let mut wins = 0.0;
+    for x in a {
+        for y in b {
+            if x > y {
+                wins += 1.0;
+            } else if x == y {
+                wins += 0.5;
+            }
+        }
+    }
+    wins / (a.len() * b.len()) as f32
+}
+
+pub fn magnitude(a12: f32) -> Magnitude {
+    let distance = (a12 - 0.5).abs();
+    if distance < 0.06 {
+        Magnitude::Negligible
+    } else if distance < 0.14 {
+        Magnitude::Small
+    } else if distance < 0.21 {
+        Magnitude::Medium
+    } else {
+        Magnitude::Large
+    }
+}
+
+// Compares one measure across configurations and says which ones are
+// significantly better at level `alpha`. Two configurations are decided by
+// Mann-Whitney, more by Kruskal-Wallis followed by Dunn's test. Samples must
+// be paired by trial for the Wilcoxon column, as seeded experiments are.
+pub fn comparison_report(measure: &str,
+                         names: &[String],
+                         samples: &[Vec<f32>],
+                         higher_is_better: bool,
+                         alpha: f32) -> String {
+    let mut report = format!("{} ({} is better)\n", measure, if higher_is_better { "higher" } else { "lower" });
+    for (name, sample) in names.iter().zip(samples) {
+        let mut sorted = sample.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        report.push_str(&format!("  {}: median {}, quartiles {} .. {}\n",
+                                 name,
+                                 format_quantile(&sorted, 0.5),
+                                 format_quantile(&sorted, 0.25),
+                                 format_quantile(&sorted, 0.75)));
+    }
+    if samples.len() < 2 {
+        return report;
+    }
+
+    let post_hoc = if samples.len() > 2 {
+        let test = kruskal_wallis(samples);
+        report.push_str(&format!("  Kruskal-Wallis: H = {:.3}, p = {:.4}\n", test.statistic, test.p_value));
+        if test.p_value >= alpha {
+            report.push_str("  => no significant difference between the configurations\n");
+            return report;
+        }
+        Some(dunn(samples))
+    } else {
+        None
+    };
+
+    let mut pair = 0;
+    for a in 0..samples.len() {
+        for b in a + 1..samples.len() {
+            let mann_whitney = mann_whitney_u(&samples[a], &samples[b]);
+            let effect = a12(&samples[a], &samples[b]);
+            let mut line = format!("  {} vs {}: Mann-Whitney U = {}, p = {:.4}",
+                                   names[a], names[b], mann_whitney.statistic, mann_whitney.p_value);
+            if samples[a].len() == samples[b].len() {
+                let wilcoxon = wilcoxon_signed_rank(&samples[a], &samples[b]);
+                line.push_str(&format!("; Wilcoxon W+ = {}, p = {:.4}", wilcoxon.statistic, wilcoxon.p_value));
+            }
+            let p_value = match post_hoc {
+                Some(ref post_hoc) => {
+                    line.push_str(&format!("; Dunn z = {:.3}, adjusted p = {:.4}", post_hoc[pair].z, post_hoc[pair].p_value));
+                    post_hoc[pair].p_value
+                },
+                None => mann_whitney.p_value,
+            };
+            line.push_str(&format!("; A12 = {:.3} ({:?})\n", effect, magnitude(effect)));
+            report.push_str(&line);
+
+            let a_is_better = (effect > 0.5) == higher_is_better;
+            if p_value < alpha && effect != 0.5 {
+                let (better, worse) = if a_is_better { (a, b) } else { (b, a) };
+                report.push_str(&format!("  => {} is significantly better than {}\n", names[better], names[worse]));
+            } else {
+                report.push_str(&format!("  => no significant difference between {} and {}\n", names[a], names[b]));
+            }
+            pair += 1;
+        }
+    }
+    report
+}
+
+// Infinite values stand for runs that never reached the target.
+fn format_quantile(sorted: &[f32], q: f32) -> String {
+    let value = quantile(sorted, q);
+    if value.is_infinite() { "not reached".to_string() } else { value.to_string() }
+}
+
+// Two-sided p-value of a normally distributed `deviation` from its mean.
+fn two_sided_p(deviation: f64, variance: f64) -> f32 {
+    if variance <= 0.0 {
+        return 1.0;
+    }
+    (erfc(deviation.abs() / (2.0 * variance).sqrt())).min(1.0) as f32
+}
+
+// Complementary error function, Chebyshev fit with relative error below
+// 1.2e-7 everywhere.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = -z * z - 1.26551223 + t * (1.00002368 + t * (0.37409196 + t * (0.09678418 +
+        t * (-0.18628806 + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 +
+        t * (-0.82215223 + t * 0.17087277))))))));
+    let value = t * polynomial.exp();
+    if x >= 0.0 { value } else { 2.0 - value }
+}
+
+// Lanczos approximation of ln(Gamma(x)) for x > 0.
+fn ln_gamma(x: f64) -> f64 {
+    let coefficients = [76.18009172947146, -86.50532032941677, 24.01409824083091,
+                        -1.231739572450155, 0.1208650973866179e-2, -0.5395239384953e-5];
+    let mut series = 1.000000000190015;
+    let mut y = x;
+    for coefficient in coefficients.iter() {
+        y += 1.0;
+        series += coefficient / y;
+    }
+    let t = x + 5.5;
+    (2.5066282746310005 * series / x).ln() + (x + 0.5) * t.ln() - t
+}
+
+// Q(s, x) = Gamma(s, x) / Gamma(s), the chi-squared survival function at
+// 2x with 2s degrees of freedom. Series below s + 1, continued fraction above.
+fn upper_regularized_gamma(s: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + s * x.ln() - ln_gamma(s)).exp();
+
+    if x < s + 1.0 {
+        let mut term = 1.0 / s;
+        let mut sum = term;
+        let mut a = s;
+        for _ in 0..500 {
+            a += 1.0;
+            term *= x / a;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-12 {
+                break;
+            }
+        }
+        (1.0 - sum * prefactor).max(0.0)
+    } else {
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - s;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut fraction = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - s);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            fraction *= delta;
+            if (delta - 1.0).abs() < 1e-12 {
+                break;
+            }
+        }
+        (fraction * prefactor).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn ranks_share_ties() {
+        let (ranks, ties) = ranks(&[3.0, 1.0, 3.0, 2.0, 3.0]);
+        assert_eq!(ranks, vec![4.0, 1.0, 4.0, 2.0, 4.0]);
+        assert_close(ties, 24.0, 1e-9);
+    }
+
+    #[test]
+    fn special_functions_match_reference_values() {
+        assert_close(erfc(0.0), 1.0, 1e-7);
+        assert_close(erfc(1.0), 0.157299207, 1e-7);
+        assert_close(erfc(-1.0), 1.842700793, 1e-7);
+        assert_close(ln_gamma(0.5), 0.572364943, 1e-9);
+        assert_close(ln_gamma(5.0), 24.0f64.ln(), 1e-9);
+    }
+
+    #[test]
+    fn chi_squared_survival_matches_tables() {
+        // 5% critical values at 1, 2 and 5 degrees of freedom
+        assert_close(upper_regularized_gamma(0.5, 3.841 / 2.0), 0.05, 1e-4);
+        assert_close(upper_regularized_gamma(1.0, 5.991 / 2.0), 0.05, 1e-4);
+        assert_close(upper_regularized_gamma(2.5, 11.070 / 2.0), 0.05, 1e-4);
+        // Q(1, x) = exp(-x), on both sides of the series/fraction switch
+        for &x in [0.1, 1.5, 2.5, 10.0].iter() {
+            assert_close(upper_regularized_gamma(1.0, x), (-x).exp(), 1e-9);
+        }
+    }
+
+    // Hollander & Wolfe examples, as in the R documentation of wilcox.test
+    // and kruskal.test; p-values from the normal approximation without
+    // continuity correction.
+    #[test]
+    fn mann_whitney_matches_reference_example() {
+        let x = [0.80, 0.83, 1.89, 1.04, 1.45, 1.38, 1.91, 1.64, 0.73, 1.46];
+        let y = [1.15, 0.88, 0.90, 0.74, 1.21];
+        let test = mann_whitney_u(&x, &y);
+        assert_eq!(test.statistic, 35.0);
+        assert_close(test.p_value as f64, 0.2207, 1e-4);
+    }
+
+    #[test]
+    fn wilcoxon_matches_reference_example() {
+        let x = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+        let y = [0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.14, 1.29];
+        let test = wilcoxon_signed_rank(&x, &y);
+        assert_eq!(test.statistic, 40.0);
+        assert_close(test.p_value as f64, 0.0382, 1e-4);
+    }
+
+    #[test]
+    fn kruskal_wallis_matches_reference_example() {
+        let groups = vec![vec![2.9, 3.0, 2.5, 2.6, 3.2],
+                          vec![3.8, 2.7, 4.0, 2.4],
+                          vec![2.8, 3.4, 3.7, 2.2, 2.0]];
+        let test = kruskal_wallis(&groups);
+        assert_close(test.statistic as f64, 0.7714, 1e-4);
+        assert_close(test.p_value as f64, 0.6800, 1e-4);
+    }
+
+    #[test]
+    fn holm_matches_textbook_vector() {
+        let adjusted = holm(&[0.01, 0.04, 0.03, 0.005]);
+        for (&actual, &expected) in adjusted.iter().zip([0.03, 0.06, 0.06, 0.02].iter()) {
+            assert_close(actual as f64, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn a12_counts_ties_as_half() {
+        assert_eq!(a12(&[1.0, 2.0], &[1.0, 0.0]), 0.875);
+        assert_eq!(magnitude(0.875), Magnitude::Large);
+    }
+
+    #[test]
+    fn report_says_when_the_target_was_not_reached() {
+        let names = vec!["a".to_string(), "b".to_string()];
+        let samples = vec![vec![f32::INFINITY; 4], vec![10.0, 20.0, f32::INFINITY, f32::INFINITY]];
+        let report = comparison_report("evaluations", &names, &samples, false, 0.05);
+        assert!(report.contains("a: median not reached, quartiles not reached .. not reached"), "{}", report);
+        assert!(!report.contains("NaN"), "{}", report);
+    }
+}
@@ -0,0 +1,185 @@
+extern crate rand;
+use self::rand::{Rng, SeedableRng, StdRng};
+
+use std::cmp::Ordering;
+
+use genetic::experiment::{Summary, quantile};
+
+// Values a swept parameter can take.
+//  - List: exactly these values.
+//  - Range: anywhere in [start, end]; grids take `steps` evenly spaced values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Values {
+    List(Vec<f32>),
+    Range { start: f32, end: f32, steps: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub values: Values,
+}
+
+// How settings are picked from the parameter space.
+//  - Grid: every combination.
+//  - Random: `samples` independent uniform draws.
+//  - LatinHypercube: `samples` draws that cover each parameter's range in
+//    `samples` equal strata, one draw per stratum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    Grid,
+    Random { samples: usize },
+    LatinHypercube { samples: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    pub parameters: Vec<Parameter>,
+    pub strategy: Strategy,
+    // Seeds the random and Latin hypercube draws.
+    pub seed: usize,
+}
+
+// A setting is one value per parameter, in the order of `Sweep::parameters`.
+pub type Setting = Vec<f32>;
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub setting: Setting,
+    pub summary: Summary,
+}
+
+impl Values {
+    fn grid(&self) -> Vec<f32> {
+        match *self {
+            Values::List(ref values) => values.clone(),
+            Values::Range { start, end, steps } if steps > 1 => (0..steps)
+                .map(|step| start + (end - start) * step as f32 / (steps - 1) as f32)
+                .collect(),
+            Values::Range { start, .. } => vec![start],
+        }
+    }
+
+    // Value at `position` in [0, 1) along the list or the range.
+    fn at(&self, position: f32) -> f32 {
+        match *self {
+            Values::List(ref values) => {
+                let index = (position * values.len() as f32) as usize;
+                values[index.min(values.len() - 1)]
+            },
+            Values::Range { start, end, .. } => start + (end - start) * position,
+        }
+    }
+}
+
+impl Sweep {
+    pub fn new(parameters: Vec<Parameter>, strategy: Strategy) -> Sweep {
+        Sweep { parameters: parameters, strategy: strategy, seed: 0 }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.parameters.iter().map(|parameter| parameter.name.as_str()).collect()
+    }
+
+    pub fn settings(&self) -> Vec<Setting> {
+        let mut rng: StdRng = SeedableRng::from_seed(&[self.seed][..]);
+        match self.strategy {
+            Strategy::Grid => {
+                let mut settings: Vec<Setting> = vec![Vec::new()];
+                for parameter in &self.parameters {
+                    let values = parameter.values.grid();
+                    settings = settings.iter()
+                        .flat_map(|setting| values.iter().map(move |&value| {
+                            let mut setting = setting.clone();
+                            setting.push(value);
+                            setting
+                        }))
+                        .collect();
+                }
+                settings
+            },
+            Strategy::Random { samples } => (0..samples)
+                .map(|_| self.parameters.iter().map(|parameter| parameter.values.at(rng.gen::<f32>())).collect())
+                .collect(),
+            Strategy::LatinHypercube { samples } => {
+                let mut settings: Vec<Setting> = vec![Vec::with_capacity(self.parameters.len()); samples];
+                for parameter in &self.parameters {
+                    let mut strata: Vec<usize> = (0..samples).collect();
+                    rng.shuffle(&mut strata);
+                    for (setting, stratum) in settings.iter_mut().zip(strata) {
+                        let position = (stratum as f32 + rng.gen::<f32>()) / samples as f32;
+                        setting.push(parameter.values.at(position));
+                    }
+                }
+                settings
+            },
+        }
+    }
+}
+
+impl Outcome {
+    pub fn median_fitness(&self) -> f32 {
+        quantile(&self.summary.final_fitnesses(), 0.5)
+    }
+}
+
+// Best median final fitness first; ties go to the lower expected running
+// time, and settings that never hit the target come last among equals.
+pub fn rank(outcomes: &mut Vec<Outcome>) {
+    outcomes.sort_by(|a, b| {
+        b.median_fitness().partial_cmp(&a.median_fitness())
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| match (a.summary.expected_running_time(), b.summary.expected_running_time()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+    });
+}
+
+// Ranked outcomes as CSV, one row per setting.
+pub fn table_csv(names: &[&str], outcomes: &[Outcome]) -> String {
+    let mut csv = format!("rank,{},median_fitness,mean_fitness,success_rate,expected_running_time\n",
+                          names.join(","));
+    for (rank, outcome) in outcomes.iter().enumerate() {
+        let values: Vec<String> = outcome.setting.iter().map(|value| value.to_string()).collect();
+        let fitnesses = outcome.summary.final_fitnesses();
+        csv.push_str(&format!("{},{},{},{},{},{}\n",
+                              rank + 1,
+                              values.join(","),
+                              outcome.median_fitness(),
+                              fitnesses.iter().sum::<f32>() / fitnesses.len().max(1) as f32,
+                              outcome.summary.success_rate().map_or(String::new(), |rate| rate.to_string()),
+                              outcome.summary.expected_running_time().map_or(String::new(), |ert| ert.to_string())));
+    }
+    csv
+}
+
+// Ranked outcomes as an aligned text table.
+pub fn table(names: &[&str], outcomes: &[Outcome]) -> String {
+    let mut header: Vec<String> = vec!["rank".to_string()];
+    header.extend(names.iter().map(|name| name.to_string()));
+    header.extend(["median", "success", "ERT"].iter().map(|column| column.to_string()));
+
+    let mut rows = vec![header];
+    for (rank, outcome) in outcomes.iter().enumerate() {
+        let mut row = vec![(rank + 1).to_string()];
+        row.extend(outcome.setting.iter().map(|value| format!("{}", value)));
+        row.push(format!("{:.4}", outcome.median_fitness()));
+        row.push(outcome.summary.success_rate().map_or("-".to_string(), |rate| format!("{:.0}%", 100.0 * rate)));
+        row.push(outcome.summary.expected_running_time().map_or("-".to_string(), |ert| format!("{:.0}", ert)));
+        rows.push(row);
+    }
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+    let mut table = String::new();
+    for row in rows {
+        let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, &width)| format!("{:>1$}", cell, width)).collect();
+        table.push_str(&cells.join("  "));
+        table.push('\n');
+    }
+    table
+}
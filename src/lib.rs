@@ -0,0 +1,8 @@
+// Genetic algorithm engine: populations and their operators, fitness
+// functions and problems, and the tools to run and compare experiments.
+// The `genetic-thing` binary is a command line front end to it.
+
+#[macro_use]
+extern crate log;
+
+pub mod genetic;
@@ -1,122 +1,57 @@
-use std::f32;
+use std::env;
+use std::process;
 
-extern crate gnuplot;
-use gnuplot::{AxesCommon, Figure, Color};
-use gnuplot::LabelOption::TextColor;
-use gnuplot::PlotOption::LineWidth;
+extern crate genetic_thing;
+extern crate num;
 
-mod genetic;
-use genetic::helpers::Range;
-use genetic::helpers::{hamming_distance, euclidean_distance_int, euclidean_distance_float};
-use genetic::population::{Population};
-use genetic::fitness::{max_alternating_bits,
-                       max_alternating_even_odd,
-                       pattern_recognition,
-                       min_dejong,
-                       n_queens,
-                       path_fitness,
-                       deceptive_f3,
-                       deceptive_f3s,
-                       deceptive_4};
-use genetic::crossover::{one_point_crossover,
-                         one_point_crossover_3,
-                         uniform_average_crossover,
-                         uniform_crossover,
-                         uniform_crossover_3,
-                         partially_matched_crossover};
-use genetic::mutation::{bit_flip,
-                        swap_position,
-                        random_int,
-                        delta_mutation,
-                        gaussian_mutation};
+#[macro_use]
+extern crate log;
 
-fn main() {
-    let total_generations = 10000;
-    let population_size = 50;
-    let genome_size = 30;
-
-    let crossover_probability = 0.98;
-    let mutation_probability = 0.001;
-    
-    let has_elitism = true;
-    let has_scaling = true;
-    let has_generation_gap = true;
-    let has_fitness_sharing = true;
-    let crowding_factor = 20;
+mod cli;
+use cli::options::{parse_options, USAGE};
+use cli::logging::init_logging;
+use cli::problems::{run_deceptive, run_maze, run_queens, run_benchmark, run_bit_strings, run_coloring, run_pattern,
+                    run_tsp};
+use cli::experiments::{run_experiment, run_sweep, run_comparison};
 
-    let distance_function = hamming_distance;
-    
-    let fitness_function = deceptive_f3s;
-    let mutation_function = bit_flip;
-    
-    let mut population = Population::<u8>::new(population_size,
-                                               genome_size,
-                                               crossover_probability,
-                                               mutation_probability,
-                                               Range::new(0, 1),
-                                               has_elitism,
-                                               has_scaling,
-                                               has_generation_gap,
-                                               has_fitness_sharing,
-                                               crowding_factor,
-                                               distance_function,
-                                               fitness_function,
-                                               one_point_crossover,
-                                               mutation_function);
-    
-    println!("Initial population");
-    population.print();
-
-    for current_generation in 0..total_generations {
-        population.iterate_generation(current_generation, total_generations);
-        println!("\nGeneration: {}", current_generation);
-        //population.print();
+fn main() {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_options(arguments.clone()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(1);
+        }
+    };
+    if let Err(message) = init_logging(&options) {
+        eprintln!("{}", message);
+        process::exit(1);
     }
-
-    population.print_best_individual_diagnostic();
-    
-    show_convergence_plot(&population.average_fitness_in_generation,
-                          &population.best_fitness_in_generation);
-    
-    show_diversity_plot(&population.diversity_in_generation);
-}
-
-fn show_convergence_plot(average_fitnesses: &Vec<f32>, best_fitnesses: &Vec<f32>) {
-    let generations: Vec<usize> = (0..average_fitnesses.len()).collect();
-
-    let mut fg = Figure::new();
-    fg.set_terminal("wxt size 800, 400", "");
-    {
-        let axes = fg.axes2d()
-            .lines(&generations,
-                   average_fitnesses,
-                   &[Color("#505050"), LineWidth(1.2)])
-            .lines(&generations,
-                   best_fitnesses,
-                   &[Color("#0072bd"), LineWidth(1.2)]);
-
-        let label_options = &[gnuplot::LabelOption::TextColor("black")];
-        axes.set_x_label("Gerações", label_options);
-        axes.set_y_label("Fitness", label_options);
+    if !options.compare.is_empty() {
+        run_comparison(&arguments, options);
+        return;
+    }
+    if !options.sweep.is_empty() {
+        run_sweep(options);
+        return;
+    }
+    if options.trials.is_some() {
+        run_experiment(options);
+        return;
     }
-    fg.show();
-} 
-
-fn show_diversity_plot(diversity_in_generations: &Vec<f32>) {
-    let generations: Vec<usize> = (0..diversity_in_generations.len()).collect();
 
-    let mut fg = Figure::new();
-    fg.set_terminal("wxt size 800, 400", "");
-    {
-        let axes = fg.axes2d()
-            .lines(&generations,
-                   diversity_in_generations,
-                   &[Color("#505050"), LineWidth(1.2)]);
-    
-        let label_options = &[gnuplot::LabelOption::TextColor("black")];
-        axes.set_x_label("Gerações", label_options);
-        axes.set_y_label("Diversidade", label_options);
+    match options.problem.as_str() {
+        "deceptive" => run_deceptive(&options),
+        "maze" => run_maze(&options),
+        "queens" => run_queens(&options),
+        "tsp" => run_tsp(&options),
+        "benchmark" => run_benchmark(&options),
+        "binary" | "knapsack" | "maxsat" => run_bit_strings(&options),
+        "coloring" => run_coloring(&options),
+        "pattern" => run_pattern(&options),
+        other => {
+            eprintln!("unknown problem {}\n{}", other, USAGE);
+            process::exit(1);
+        }
     }
-    fg.show();
 }
-